@@ -0,0 +1,239 @@
+use crate::{is_null_token, record_warning, Scalar};
+use serde::{
+    de::{Error, Unexpected},
+    Deserialize, Deserializer, Serializer,
+};
+
+const TRUE_TOKENS: &[&str] = &["1", "true", "yes", "y"];
+const FALSE_TOKENS: &[&str] = &["0", "false", "no", "n"];
+
+// The exact tokens accepted by the original single-purpose bool modules
+const STRICT_TRUE_TOKENS: &[&str] = &["1", "true", "True", "Yes", "Y"];
+const STRICT_FALSE_TOKENS: &[&str] = &["0", "false", "False", "No", "N"];
+
+// Further spellings accepted when lenient, on top of the standard tokens
+const LENIENT_TRUE_TOKENS: &[&str] = &["t", "on", "checked", "1.0"];
+const LENIENT_FALSE_TOKENS: &[&str] = &["f", "off", "unchecked", "0.0"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Strictness {
+    Strict,
+    Standard,
+    Lenient,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Output {
+    Native,
+    OneZero,
+    TrueFalse,
+    YesNo,
+    YN,
+    Int,
+}
+
+enum Parsed {
+    Value(bool),
+    Null,
+    Unrecognized(String),
+}
+
+fn classify(s: &str, strictness: Strictness) -> Parsed {
+    let matches = |tokens: &[&str]| {
+        if strictness == Strictness::Strict {
+            tokens.contains(&s)
+        } else {
+            let trimmed = s.trim();
+            tokens.iter().any(|t| t.eq_ignore_ascii_case(trimmed))
+        }
+    };
+
+    let (true_tokens, false_tokens) = match strictness {
+        Strictness::Strict => (STRICT_TRUE_TOKENS, STRICT_FALSE_TOKENS),
+        _ => (TRUE_TOKENS, FALSE_TOKENS),
+    };
+
+    let lenient = strictness == Strictness::Lenient;
+    if matches(true_tokens) || (lenient && matches(LENIENT_TRUE_TOKENS)) {
+        Parsed::Value(true)
+    } else if matches(false_tokens) || (lenient && matches(LENIENT_FALSE_TOKENS)) {
+        Parsed::Value(false)
+    } else if is_null_token(s) {
+        Parsed::Null
+    } else {
+        Parsed::Unrecognized(s.to_string())
+    }
+}
+
+fn parse<'de, D>(deserializer: D, strictness: Strictness) -> Result<Parsed, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match Scalar::deserialize(deserializer)? {
+        Scalar::Bool(b) => Parsed::Value(b),
+        Scalar::Empty => Parsed::Null,
+        Scalar::Str(s) => classify(&s, strictness),
+        x => match x.as_i64() {
+            Some(1) => Parsed::Value(true),
+            Some(0) => Parsed::Value(false),
+            _ => Parsed::Unrecognized(x.to_string()),
+        },
+    })
+}
+
+fn required<E: Error>(parsed: Parsed) -> Result<bool, E> {
+    match parsed {
+        Parsed::Value(b) => Ok(b),
+        Parsed::Null => Err(E::custom("boolean field cannot be empty")),
+        Parsed::Unrecognized(s) => Err(E::invalid_value(Unexpected::Str(&s), &"a boolean token")),
+    }
+}
+
+// Unrecognized tokens are an error unless lenient, where they read as `None`
+// with a warning in the load report
+fn optional<E: Error>(parsed: Parsed, strictness: Strictness) -> Result<Option<bool>, E> {
+    match (parsed, strictness) {
+        (Parsed::Value(b), _) => Ok(Some(b)),
+        (Parsed::Null, _) => Ok(None),
+        (Parsed::Unrecognized(s), Strictness::Lenient) => {
+            record_warning(format!("treating unrecognized boolean {:?} as null", s));
+            Ok(None)
+        }
+        (Parsed::Unrecognized(s), _) => Err(E::invalid_value(
+            Unexpected::Str(&s),
+            &"a boolean token or null",
+        )),
+    }
+}
+
+fn write<S>(val: &bool, output: Output, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let val = *val;
+    match output {
+        Output::Native => serializer.serialize_bool(val),
+        Output::Int => serializer.serialize_u8(if val { 1 } else { 0 }),
+        Output::OneZero => serializer.serialize_str(if val { "1" } else { "0" }),
+        Output::TrueFalse => serializer.serialize_str(if val { "True" } else { "False" }),
+        Output::YesNo => serializer.serialize_str(if val { "Yes" } else { "No" }),
+        Output::YN => serializer.serialize_str(if val { "Y" } else { "N" }),
+    }
+}
+
+fn write_opt<S>(val: &Option<bool>, output: Output, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match val {
+        Some(val) => write(val, output, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+// The submodules that read like their parent but write a fixed spelling,
+// e.g. `#[serde(with = "flexible_bool::yes_no")]`
+macro_rules! output_modules {
+    ($ty:ty, $write:ident) => {
+        output_modules!(@module $ty, $write, one_zero, OneZero);
+        output_modules!(@module $ty, $write, true_false, TrueFalse);
+        output_modules!(@module $ty, $write, yes_no, YesNo);
+        output_modules!(@module $ty, $write, y_n, YN);
+        output_modules!(@module $ty, $write, int, Int);
+    };
+    (@module $ty:ty, $write:ident, $name:ident, $output:ident) => {
+        pub mod $name {
+            pub use super::{deserialize, deserialize_lenient, deserialize_strict};
+            use super::{$write, Output, Serializer};
+
+            pub fn serialize<S>(val: &$ty, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                $write(val, Output::$output, serializer)
+            }
+        }
+    };
+}
+
+pub mod flexible_bool {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<bool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        required(parse(deserializer, Strictness::Standard)?)
+    }
+
+    pub fn deserialize_strict<'de, D>(deserializer: D) -> Result<bool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        required(parse(deserializer, Strictness::Strict)?)
+    }
+
+    pub fn deserialize_lenient<'de, D>(deserializer: D) -> Result<bool, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        required(parse(deserializer, Strictness::Lenient)?)
+    }
+
+    pub fn serialize<S>(val: &bool, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        write(val, Output::Native, serializer)
+    }
+
+    output_modules!(bool, write);
+}
+
+pub mod nullable_flexible_bool {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        optional(
+            parse(deserializer, Strictness::Standard)?,
+            Strictness::Standard,
+        )
+    }
+
+    pub fn deserialize_strict<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        optional(parse(deserializer, Strictness::Strict)?, Strictness::Strict)
+    }
+
+    pub fn deserialize_lenient<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        optional(
+            parse(deserializer, Strictness::Lenient)?,
+            Strictness::Lenient,
+        )
+    }
+
+    pub fn serialize<S>(val: &Option<bool>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        write_opt(val, Output::Native, serializer)
+    }
+
+    pub fn default_true() -> Option<bool> {
+        Some(true)
+    }
+
+    pub fn default_false() -> Option<bool> {
+        Some(false)
+    }
+
+    output_modules!(Option<bool>, write_opt);
+}
@@ -0,0 +1,284 @@
+use crate::{record_unknown_code, record_warning, Scalar};
+use serde::{
+    de::{Error, Unexpected},
+    ser, Deserialize, Deserializer, Serializer,
+};
+use std::any::type_name;
+
+// e.g.
+// impl EnumCode for Airway {
+//     const CODES: &'static [(&'static str, Self)] = &[
+//         ("ETT", Airway::Ett), ("1", Airway::Ett), ("Endotracheal", Airway::Ett),
+//         ("LMA", Airway::Lma), ("2", Airway::Lma),
+//     ];
+// }
+pub trait EnumCode: Sized + Clone + PartialEq + 'static {
+    // Every accepted code, aliases and numeric ids included, matched ignoring case
+    const CODES: &'static [(&'static str, Self)];
+
+    // The code written when serializing; defaults to the variant's first listed code
+    fn code(&self) -> Option<&'static str> {
+        Self::CODES
+            .iter()
+            .find(|(_, variant)| variant == self)
+            .map(|(code, _)| *code)
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        let code = code.trim().to_lowercase();
+        Self::CODES
+            .iter()
+            .find(|(known, _)| known.to_lowercase() == code)
+            .map(|(_, variant)| variant.clone())
+    }
+}
+
+// Text is matched as written, so "01" doesn't match the code "1". Typed number cells
+// are matched by their number, so 2 and 2.0 both match the code "2".
+fn from_scalar<T: EnumCode>(scalar: &Scalar) -> Option<T> {
+    match scalar {
+        Scalar::Str(s) => T::from_code(s),
+        Scalar::Int(_) | Scalar::UInt(_) | Scalar::Float(_) => T::from_code(&scalar.to_string()),
+        Scalar::Bool(_) | Scalar::Empty => None,
+    }
+}
+
+fn unknown_code<E: Error>(scalar: &Scalar) -> E {
+    match scalar {
+        Scalar::Bool(_) | Scalar::Empty => E::invalid_type(scalar.unexpected(), &"a code"),
+        x => E::invalid_value(Unexpected::Str(&x.to_string()), &"a known code"),
+    }
+}
+
+fn write<T: EnumCode, S: Serializer>(val: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    match val.code() {
+        Some(code) => serializer.serialize_str(code),
+        None => Err(ser::Error::custom("enum variant has no code")),
+    }
+}
+
+pub mod enum_from_code {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: EnumCode,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        from_scalar(&scalar).ok_or_else(|| unknown_code(&scalar))
+    }
+
+    pub fn serialize<S, T>(val: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: EnumCode,
+    {
+        write(val, serializer)
+    }
+}
+
+pub mod enum_from_code_opt {
+    use super::*;
+
+    // Unknown codes are read as `None`, like `enum_from_id_opt`
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: EnumCode,
+    {
+        Ok(Scalar::deserialize(deserializer)
+            .ok()
+            .and_then(|scalar| from_scalar(&scalar)))
+    }
+
+    pub fn deserialize_strict<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: EnumCode,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            return Ok(None);
+        }
+
+        from_scalar(&scalar)
+            .map(Some)
+            .ok_or_else(|| unknown_code(&scalar))
+    }
+
+    pub fn deserialize_audited<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: EnumCode,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            return Ok(None);
+        }
+
+        let val = from_scalar(&scalar);
+        if val.is_none() {
+            record_warning(format!(
+                "treating unknown code {:?} as null",
+                scalar.to_string()
+            ));
+        }
+        Ok(val)
+    }
+
+    pub fn serialize<S, T>(val: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: EnumCode,
+    {
+        match val {
+            Some(val) => write(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn default<T>() -> Option<T>
+    where
+        T: Default,
+    {
+        Some(T::default())
+    }
+}
+
+pub mod enum_from_code_or_default {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: EnumCode + Default,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        Ok(from_scalar(&scalar).unwrap_or_default())
+    }
+
+    pub fn serialize<S, T>(val: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: EnumCode,
+    {
+        write(val, serializer)
+    }
+}
+
+// Keeps codes the enum doesn't list instead of failing or defaulting,
+// so new codes from the source system survive a load and can be written back out
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CodedValue<T> {
+    Known(T),
+    Unknown(String),
+}
+
+impl<T> CodedValue<T> {
+    pub fn known(&self) -> Option<&T> {
+        match self {
+            CodedValue::Known(val) => Some(val),
+            CodedValue::Unknown(_) => None,
+        }
+    }
+
+    pub fn is_known(&self) -> bool {
+        matches!(self, CodedValue::Known(_))
+    }
+
+    pub fn into_known(self) -> Option<T> {
+        match self {
+            CodedValue::Known(val) => Some(val),
+            CodedValue::Unknown(_) => None,
+        }
+    }
+}
+
+impl<T> From<T> for CodedValue<T> {
+    fn from(val: T) -> Self {
+        CodedValue::Known(val)
+    }
+}
+
+// The text of the cell as it was read, so serializing writes back the same code
+pub(crate) fn raw_code(scalar: Scalar) -> String {
+    match scalar {
+        Scalar::Str(s) => s,
+        x => x.to_string(),
+    }
+}
+
+// Unknown codes are listed in the current load's `LoadReport::unknown_codes`
+pub(crate) fn unknown<T>(raw: String) -> CodedValue<T> {
+    record_unknown_code(type_name::<T>(), raw.clone());
+    CodedValue::Unknown(raw)
+}
+
+fn coded_from_scalar<T: EnumCode, E: Error>(scalar: Scalar) -> Result<CodedValue<T>, E> {
+    if scalar.is_null() {
+        return Err(E::invalid_type(scalar.unexpected(), &"a code"));
+    }
+    Ok(match from_scalar(&scalar) {
+        Some(val) => CodedValue::Known(val),
+        None => unknown(raw_code(scalar)),
+    })
+}
+
+fn write_coded<T: EnumCode, S: Serializer>(
+    val: &CodedValue<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match val {
+        CodedValue::Known(val) => write(val, serializer),
+        CodedValue::Unknown(raw) => serializer.serialize_str(raw),
+    }
+}
+
+pub mod enum_from_code_or_unknown {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<CodedValue<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: EnumCode,
+    {
+        coded_from_scalar(Scalar::deserialize(deserializer)?)
+    }
+
+    pub fn serialize<S, T>(val: &CodedValue<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: EnumCode,
+    {
+        write_coded(val, serializer)
+    }
+}
+
+pub mod enum_from_code_or_unknown_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<CodedValue<T>>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: EnumCode,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            Ok(None)
+        } else {
+            coded_from_scalar(scalar).map(Some)
+        }
+    }
+
+    pub fn serialize<S, T>(val: &Option<CodedValue<T>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: EnumCode,
+    {
+        match val {
+            Some(val) => write_coded(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
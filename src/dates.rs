@@ -0,0 +1,51 @@
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+// Two-digit year formats must come before their `%Y` twins, which would read "21" as year 21
+pub(crate) const MM_DD_YYYY_DATE_FORMATS: &[&str] = &["%m/%d/%y", "%m/%d/%Y"];
+pub(crate) const MM_DD_YYYY_DATETIME_FORMATS: &[&str] = &[
+    "%m/%d/%y %H:%M:%S%.f",
+    "%m/%d/%y %H:%M",
+    "%m/%d/%y %I:%M:%S %p",
+    "%m/%d/%y %I:%M %p",
+    "%m/%d/%Y %H:%M:%S%.f",
+    "%m/%d/%Y %H:%M",
+    "%m/%d/%Y %I:%M:%S %p",
+    "%m/%d/%Y %I:%M %p",
+];
+
+pub(crate) const MSSQL_DATE_FORMATS: &[&str] = &["%Y-%m-%d"];
+pub(crate) const MSSQL_DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+];
+
+// Excel apparently considers 1900 to be a leap year
+pub(crate) const NUM_DAYS_1900_01_01_FROM_CE: i32 = 693594;
+
+// Reads a date written either on its own or with a time of day, which is dropped
+// unless `midnight_only` is set, in which case any other time is an error
+pub(crate) fn parse_date_or_datetime(
+    s: &str,
+    date_formats: &[&str],
+    datetime_formats: &[&str],
+    midnight_only: bool,
+) -> Result<NaiveDate, String> {
+    let s = s.trim();
+    if let Some(date) = date_formats
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(s, format).ok())
+    {
+        return Ok(date);
+    }
+
+    let datetime = datetime_formats
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+        .ok_or_else(|| format!("invalid date: {}", s))?;
+    if midnight_only && datetime.time() != NaiveTime::MIN {
+        return Err(format!("expected a date without a time of day: {}", s));
+    }
+    Ok(datetime.date())
+}
@@ -0,0 +1,227 @@
+use crate::Scalar;
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+use std::str::FromStr;
+
+const CURRENCY_SYMBOLS: &[char] = &['$', '€', '£', '¥'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NegativeStyle {
+    Minus,
+    Parentheses,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurrencyFormat {
+    pub symbol: &'static str,
+    pub thousands_separator: Option<char>,
+    pub precision: u32,
+    pub negative_style: NegativeStyle,
+}
+
+impl CurrencyFormat {
+    // Matches the output of the f64 `currency` module
+    pub const DOLLARS: CurrencyFormat = CurrencyFormat {
+        symbol: "$",
+        thousands_separator: None,
+        precision: 2,
+        negative_style: NegativeStyle::Minus,
+    };
+
+    pub const ACCOUNTING: CurrencyFormat = CurrencyFormat {
+        symbol: "$",
+        thousands_separator: Some(','),
+        precision: 2,
+        negative_style: NegativeStyle::Parentheses,
+    };
+
+    pub const PLAIN: CurrencyFormat = CurrencyFormat {
+        symbol: "",
+        thousands_separator: None,
+        precision: 2,
+        negative_style: NegativeStyle::Minus,
+    };
+
+    pub fn format(&self, val: &Decimal) -> String {
+        let rounded =
+            val.round_dp_with_strategy(self.precision, RoundingStrategy::MidpointAwayFromZero);
+        let digits = format!("{:.*}", self.precision as usize, rounded.abs());
+        let (whole, fraction) = match digits.find('.') {
+            Some(i) => digits.split_at(i),
+            None => (digits.as_str(), ""),
+        };
+
+        let mut grouped = String::with_capacity(digits.len() + whole.len() / 3);
+        for (i, c) in whole.chars().enumerate() {
+            if let Some(separator) = self.thousands_separator {
+                if i > 0 && (whole.len() - i) % 3 == 0 {
+                    grouped.push(separator);
+                }
+            }
+            grouped.push(c);
+        }
+
+        let amount = format!("{}{}{}", self.symbol, grouped, fraction);
+        if rounded.is_sign_negative() && !rounded.is_zero() {
+            match self.negative_style {
+                NegativeStyle::Minus => format!("-{}", amount),
+                NegativeStyle::Parentheses => format!("({})", amount),
+            }
+        } else {
+            amount
+        }
+    }
+}
+
+pub trait CurrencyStyle {
+    const FORMAT: CurrencyFormat;
+}
+
+pub struct DollarStyle;
+
+impl CurrencyStyle for DollarStyle {
+    const FORMAT: CurrencyFormat = CurrencyFormat::DOLLARS;
+}
+
+pub struct AccountingStyle;
+
+impl CurrencyStyle for AccountingStyle {
+    const FORMAT: CurrencyFormat = CurrencyFormat::ACCOUNTING;
+}
+
+pub struct PlainStyle;
+
+impl CurrencyStyle for PlainStyle {
+    const FORMAT: CurrencyFormat = CurrencyFormat::PLAIN;
+}
+
+// Accepts forms like "$1,234.56", "(1,234.56)", "1,234.56-", "1234.56 USD" and "€12".
+// Takes at most one currency symbol and one sign marker, so "--5", "-(5)" and "$-$5" are
+// rejected, as is a comma after the last dot, as in the European "1.234,56 €"
+pub fn parse_currency(s: &str) -> Option<Decimal> {
+    let mut s = s.trim();
+    let mut signs = 0;
+    let mut negative = false;
+
+    if s.starts_with('(') && s.ends_with(')') {
+        negative = true;
+        signs += 1;
+        s = &s[1..s.len() - 1];
+    }
+    if s.chars().filter(|c| CURRENCY_SYMBOLS.contains(c)).count() > 1 {
+        return None;
+    }
+    if let (Some(dot), Some(comma)) = (s.rfind('.'), s.rfind(',')) {
+        if comma > dot {
+            return None;
+        }
+    }
+
+    let mut amount: String = s
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != ',' && !CURRENCY_SYMBOLS.contains(c))
+        .collect();
+    amount = amount
+        .trim_matches(|c: char| c.is_ascii_alphabetic())
+        .to_string();
+
+    if let Some(stripped) = amount.strip_suffix('-') {
+        negative = true;
+        signs += 1;
+        amount = stripped.to_string();
+    }
+    if let Some(stripped) = amount.strip_prefix('-') {
+        negative = true;
+        signs += 1;
+        amount = stripped.to_string();
+    } else if let Some(stripped) = amount.strip_prefix('+') {
+        signs += 1;
+        amount = stripped.to_string();
+    }
+    if signs > 1 || !amount.starts_with(|c: char| c.is_ascii_digit() || c == '.') {
+        return None;
+    }
+
+    let val = Decimal::from_str(&amount)
+        .or_else(|_| Decimal::from_scientific(&amount))
+        .ok()?;
+    Some(if negative { -val } else { val })
+}
+
+fn from_scalar<E: Error>(scalar: Scalar) -> Result<Decimal, E> {
+    match scalar {
+        Scalar::Str(s) => {
+            parse_currency(&s).ok_or_else(|| E::custom(format!("invalid currency amount: {}", s)))
+        }
+        Scalar::Int(i) => Ok(Decimal::from(i)),
+        Scalar::UInt(u) => Ok(Decimal::from(u)),
+        // Only typed cells such as xlsx numbers arrive as floats; CSV text is parsed exactly
+        // above. The shortest round-tripping representation is what the cell actually held
+        Scalar::Float(f) => Decimal::from_str(&f.to_string())
+            .or_else(|_| Decimal::from_scientific(&format!("{:e}", f)))
+            .map_err(|e| E::custom(format!("invalid currency amount: {} {:?}", f, e))),
+        x => Err(E::invalid_type(x.unexpected(), &"a currency amount")),
+    }
+}
+
+pub mod decimal_currency {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Decimal, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        from_scalar(Scalar::deserialize(deserializer)?)
+    }
+
+    pub fn serialize<S>(val: &Decimal, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_as::<DollarStyle, S>(val, serializer)
+    }
+
+    // e.g. `#[serde(serialize_with = "decimal_currency::serialize_as::<AccountingStyle, _>")]`
+    pub fn serialize_as<F, S>(val: &Decimal, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        F: CurrencyStyle,
+        S: Serializer,
+    {
+        serializer.serialize_str(&F::FORMAT.format(val))
+    }
+}
+
+pub mod decimal_currency_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Decimal>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            Ok(None)
+        } else {
+            from_scalar(scalar).map(Some)
+        }
+    }
+
+    pub fn serialize<S>(val: &Option<Decimal>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_as::<DollarStyle, S>(val, serializer)
+    }
+
+    pub fn serialize_as<F, S>(val: &Option<Decimal>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        F: CurrencyStyle,
+        S: Serializer,
+    {
+        match val {
+            Some(val) => serializer.serialize_str(&F::FORMAT.format(val)),
+            None => serializer.serialize_none(),
+        }
+    }
+}
@@ -0,0 +1,264 @@
+use crate::{
+    dates::MM_DD_YYYY_DATETIME_FORMATS,
+    is_null_token, iso8601_date, iso8601_datetime,
+    load::{detected_date_format, record_ambiguous_dates, set_detected_date_formats},
+    Scalar, TimestampScale,
+};
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+// Ranked: ISO first, then month-first, then day-first, so ambiguous values read as US dates.
+// Two-digit year formats come before their `%Y` twins, which would read "21" as year 21.
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d",
+    "%Y/%m/%d",
+    "%Y%m%d",
+    "%m/%d/%y",
+    "%m/%d/%Y",
+    "%m-%d-%Y",
+    "%d/%m/%y",
+    "%d/%m/%Y",
+    "%d.%m.%Y",
+    "%d-%b-%y",
+    "%d-%b-%Y",
+    "%d %b %Y",
+    "%b %d, %Y",
+    "%b %d %Y",
+];
+
+const ISO_DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+];
+
+const DAY_FIRST_DATETIME_FORMATS: &[&str] = &[
+    "%d/%m/%y %H:%M:%S%.f",
+    "%d/%m/%y %H:%M",
+    "%d/%m/%Y %H:%M:%S%.f",
+    "%d/%m/%Y %H:%M",
+    "%d.%m.%Y %H:%M:%S",
+    "%d.%m.%Y %H:%M",
+    "%d-%b-%Y %H:%M:%S",
+    "%d-%b-%Y %H:%M",
+];
+
+fn ranked_formats() -> impl Iterator<Item = &'static str> {
+    DATE_FORMATS
+        .iter()
+        .chain(ISO_DATETIME_FORMATS)
+        .chain(MM_DD_YYYY_DATETIME_FORMATS)
+        .chain(DAY_FIRST_DATETIME_FORMATS)
+        .copied()
+}
+
+fn has_time(format: &str) -> bool {
+    format.contains("%H") || format.contains("%I")
+}
+
+// chrono's `%Y` also takes one to three digits, which would read "03/04/21" with "%Y/%m/%d"
+// as year 3, so a `%Y` year must have four digits
+pub fn parse_with_format(s: &str, format: &str) -> Option<NaiveDateTime> {
+    let s = s.trim();
+    let parsed = if has_time(format) {
+        NaiveDateTime::parse_from_str(s, format).ok()
+    } else {
+        NaiveDate::parse_from_str(s, format)
+            .ok()
+            .map(|date| date.and_time(chrono::NaiveTime::MIN))
+    }?;
+    if format.contains("%Y") && parsed.year() < 1000 {
+        None
+    } else {
+        Some(parsed)
+    }
+}
+
+// Within a load that detects date formats, the current column's format is tried first
+pub fn parse_any_datetime(s: &str) -> Option<NaiveDateTime> {
+    detected_date_format()
+        .into_iter()
+        .chain(ranked_formats())
+        .find_map(|format| parse_with_format(s, format))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetectedDateFormat {
+    pub format: &'static str,
+    // Another format also reads every value, but to different dates,
+    // e.g. a column of "03/04/2021" that could be MM/DD or DD/MM
+    pub ambiguous: bool,
+}
+
+// Picks the highest ranked format that reads every non-null value in a column
+pub fn detect_date_format<I, S>(values: I) -> Option<DetectedDateFormat>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let values: Vec<S> = values
+        .into_iter()
+        .filter(|value| !is_null_token(value.as_ref()))
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+
+    let parse_all = |format: &str| -> Option<Vec<NaiveDateTime>> {
+        values
+            .iter()
+            .map(|value| parse_with_format(value.as_ref(), format))
+            .collect()
+    };
+
+    let mut candidates = ranked_formats();
+    let (format, parsed) = candidates
+        .by_ref()
+        .find_map(|format| parse_all(format).map(|parsed| (format, parsed)))?;
+    let ambiguous = candidates.any(|other| match parse_all(other) {
+        Some(other_parsed) => other_parsed != parsed,
+        None => false,
+    });
+
+    Some(DetectedDateFormat { format, ambiguous })
+}
+
+// Records each column's format for the rest of the load, reporting the ambiguous ones.
+// `headers` names the columns by position, as row fields are matched to them.
+pub(crate) fn detect_columns(headers: Vec<String>, columns: Vec<Vec<String>>) {
+    let formats = columns
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let detection = detect_date_format(column)?;
+            if detection.ambiguous {
+                let name = headers.get(i).cloned().unwrap_or_else(|| i.to_string());
+                record_ambiguous_dates(name, detection.format);
+            }
+            Some(detection.format)
+        })
+        .collect();
+    set_detected_date_formats(headers, formats);
+}
+
+pub(crate) fn transpose<I, R, S>(rows: I) -> Vec<Vec<String>>
+where
+    I: IntoIterator<Item = R>,
+    R: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let mut columns: Vec<Vec<String>> = Vec::new();
+    for row in rows {
+        for (i, value) in row.into_iter().enumerate() {
+            if columns.len() <= i {
+                columns.push(Vec::new());
+            }
+            columns[i].push(value.into());
+        }
+    }
+    columns
+}
+
+// Numbers are tried as text first for values like 20210314, then as Excel date serials
+fn from_scalar<E: Error>(scalar: Scalar) -> Result<NaiveDateTime, E> {
+    let parsed = match &scalar {
+        Scalar::Str(s) => parse_any_datetime(s),
+        Scalar::Float(f) => TimestampScale::OleAutomation.from_f64(*f),
+        Scalar::Int(_) | Scalar::UInt(_) => parse_any_datetime(&scalar.to_string())
+            .or_else(|| TimestampScale::OleAutomation.from_scalar(&scalar)),
+        x => return Err(E::invalid_type(x.unexpected(), &"a date")),
+    };
+    parsed.ok_or_else(|| E::custom(format!("invalid date: {}", scalar)))
+}
+
+pub mod any_date {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        from_scalar(Scalar::deserialize(deserializer)?).map(|dt| dt.date())
+    }
+
+    pub fn serialize<S>(val: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        iso8601_date::serialize(val, serializer)
+    }
+}
+
+pub mod any_date_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            Ok(None)
+        } else {
+            from_scalar(scalar).map(|dt| Some(dt.date()))
+        }
+    }
+
+    pub fn serialize<S>(val: &Option<NaiveDate>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => iso8601_date::serialize(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+pub mod any_datetime {
+    use super::*;
+
+    // Date-only values are read as midnight
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<NaiveDateTime, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        from_scalar(Scalar::deserialize(deserializer)?)
+    }
+
+    pub fn serialize<S>(val: &NaiveDateTime, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        iso8601_datetime::serialize(val, serializer)
+    }
+}
+
+pub mod any_datetime_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<NaiveDateTime>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            Ok(None)
+        } else {
+            from_scalar(scalar).map(Some)
+        }
+    }
+
+    pub fn serialize<S>(val: &Option<NaiveDateTime>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => iso8601_datetime::serialize(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use crate::{Dimension, Quantity, Scalar, Unit};
+use chrono::Duration;
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+const MILLIS_PER_DAY: f64 = 86_400_000.0;
+const MILLIS_PER_MINUTE: f64 = 60_000.0;
+
+fn from_millis(millis: f64) -> Option<Duration> {
+    let millis = millis.round();
+    if millis.is_finite() && millis.abs() < i64::MAX as f64 {
+        Duration::try_milliseconds(millis as i64)
+    } else {
+        None
+    }
+}
+
+// Accepts "01:25", "1:25:30", "85 min", "1h25m", "85" (minutes) and ISO 8601 like "PT1H25M";
+// bare numbers are always minutes, so "1.5" is 90 seconds
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let s = s.trim();
+    let (negative, unsigned) = match s.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned.trim_start()),
+        None => (false, s),
+    };
+
+    let duration = if unsigned.starts_with('P') || unsigned.starts_with('p') {
+        parse_iso8601(&unsigned[1..])?
+    } else if unsigned.contains(':') {
+        parse_clock(unsigned)?
+    } else {
+        let quantity = Quantity::parse(unsigned, Some(Dimension::Time), Some(Unit::Minute)).ok()?;
+        from_millis(quantity.si_value() * 1000.0)?
+    };
+
+    Some(if negative { -duration } else { duration })
+}
+
+fn parse_clock(s: &str) -> Option<Duration> {
+    let parts: Vec<&str> = s.split(':').collect();
+    let (hours, minutes, seconds) = match parts.as_slice() {
+        [h, m] => (h.parse::<i64>().ok()?, m.parse::<i64>().ok()?, 0.0),
+        [h, m, s] => (
+            h.parse::<i64>().ok()?,
+            m.parse::<i64>().ok()?,
+            s.parse::<f64>().ok()?,
+        ),
+        _ => return None,
+    };
+
+    if !(0..60).contains(&minutes) || !(0.0..60.0).contains(&seconds) {
+        return None;
+    }
+
+    Duration::try_hours(hours)?
+        .checked_add(&Duration::try_minutes(minutes)?)?
+        .checked_add(&from_millis(seconds * 1000.0)?)
+}
+
+// `s` is everything after the leading 'P', e.g. "1DT2H30M" or "T1.5H"
+fn parse_iso8601(s: &str) -> Option<Duration> {
+    let mut millis = 0.0;
+    let mut in_time = false;
+    let mut number = String::new();
+    let mut any = false;
+
+    for c in s.chars() {
+        match c.to_ascii_uppercase() {
+            'T' if !in_time && number.is_empty() => in_time = true,
+            c if c.is_ascii_digit() || c == '.' || c == ',' => {
+                number.push(if c == ',' { '.' } else { c })
+            }
+            designator => {
+                let value: f64 = number.parse().ok()?;
+                number.clear();
+                let unit_millis = match (designator, in_time) {
+                    ('W', false) => 7.0 * MILLIS_PER_DAY,
+                    ('D', false) => MILLIS_PER_DAY,
+                    ('H', true) => 3_600_000.0,
+                    ('M', true) => MILLIS_PER_MINUTE,
+                    ('S', true) => 1000.0,
+                    _ => return None,
+                };
+                millis += value * unit_millis;
+                any = true;
+            }
+        }
+    }
+
+    if any && number.is_empty() {
+        from_millis(millis)
+    } else {
+        None
+    }
+}
+
+// Text is read by `parse_duration`, so bare numbers there are minutes, as are typed numbers.
+// Only a typed fraction below 1 is Excel's fraction of a day, as xlsx stores durations;
+// xlsx stores every number as a float, so 85.0 is still 85 minutes.
+fn from_scalar<E: Error>(scalar: Scalar) -> Result<Duration, E> {
+    let duration = match &scalar {
+        Scalar::Str(s) => parse_duration(s),
+        Scalar::Float(f) if f.fract() != 0.0 && f.abs() < 1.0 => from_millis(f * MILLIS_PER_DAY),
+        Scalar::Float(f) => from_millis(f * MILLIS_PER_MINUTE),
+        Scalar::Int(_) | Scalar::UInt(_) => scalar.as_i64().and_then(Duration::try_minutes),
+        x => return Err(E::invalid_type(x.unexpected(), &"a duration")),
+    };
+    duration.ok_or_else(|| E::custom(format!("invalid duration: {}", scalar)))
+}
+
+fn split(val: &Duration) -> (&'static str, i64, i64, i64, i64) {
+    let sign = if *val < Duration::zero() { "-" } else { "" };
+    let millis = val.num_milliseconds().abs();
+    (
+        sign,
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000,
+    )
+}
+
+pub fn format_iso8601(val: &Duration) -> String {
+    let (sign, hours, minutes, seconds, millis) = split(val);
+    let mut formatted = format!("{}PT", sign);
+    if hours > 0 {
+        formatted.push_str(&format!("{}H", hours));
+    }
+    if minutes > 0 {
+        formatted.push_str(&format!("{}M", minutes));
+    }
+    if millis > 0 {
+        formatted.push_str(&format!("{}.{:03}S", seconds, millis));
+    } else if seconds > 0 || (hours == 0 && minutes == 0) {
+        formatted.push_str(&format!("{}S", seconds));
+    }
+    formatted
+}
+
+pub fn format_hh_mm(val: &Duration) -> String {
+    let (sign, hours, minutes, _, _) = split(val);
+    format!("{}{:02}:{:02}", sign, hours, minutes)
+}
+
+pub fn format_hh_mm_ss(val: &Duration) -> String {
+    let (sign, hours, minutes, seconds, _) = split(val);
+    format!("{}{:02}:{:02}:{:02}", sign, hours, minutes, seconds)
+}
+
+pub mod duration {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        from_scalar(Scalar::deserialize(deserializer)?)
+    }
+
+    pub fn serialize<S>(val: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&format_iso8601(val))
+    }
+
+    pub mod hh_mm {
+        pub use super::deserialize;
+        use super::{format_hh_mm, Duration, Serializer};
+
+        pub fn serialize<S>(val: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_str(&format_hh_mm(val))
+        }
+    }
+
+    pub mod hh_mm_ss {
+        pub use super::deserialize;
+        use super::{format_hh_mm_ss, Duration, Serializer};
+
+        pub fn serialize<S>(val: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_str(&format_hh_mm_ss(val))
+        }
+    }
+
+    pub mod minutes {
+        pub use super::deserialize;
+        use super::{Duration, Serializer};
+
+        pub fn serialize<S>(val: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_i64(val.num_minutes())
+        }
+    }
+}
+
+pub mod duration_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            Ok(None)
+        } else {
+            from_scalar(scalar).map(Some)
+        }
+    }
+
+    pub fn serialize<S>(val: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => serializer.serialize_str(&format_iso8601(val)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub mod hh_mm {
+        pub use super::deserialize;
+        use super::{format_hh_mm, Duration, Serializer};
+
+        pub fn serialize<S>(val: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            match val {
+                Some(val) => serializer.serialize_str(&format_hh_mm(val)),
+                None => serializer.serialize_none(),
+            }
+        }
+    }
+
+    pub mod hh_mm_ss {
+        pub use super::deserialize;
+        use super::{format_hh_mm_ss, Duration, Serializer};
+
+        pub fn serialize<S>(val: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            match val {
+                Some(val) => serializer.serialize_str(&format_hh_mm_ss(val)),
+                None => serializer.serialize_none(),
+            }
+        }
+    }
+
+    pub mod minutes {
+        pub use super::deserialize;
+        use super::{Duration, Serializer};
+
+        pub fn serialize<S>(val: &Option<Duration>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            match val {
+                Some(val) => serializer.serialize_i64(val.num_minutes()),
+                None => serializer.serialize_none(),
+            }
+        }
+    }
+}
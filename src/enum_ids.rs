@@ -0,0 +1,392 @@
+use crate::{
+    codes::{raw_code, unknown},
+    record_warning, CodedValue, Scalar,
+};
+use serde::{
+    de::{Error, Unexpected},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::convert::TryFrom;
+
+// The integer type an enum's ids are converted from and written as,
+// e.g. `#[serde(deserialize_with = "enum_from_id::deserialize_as::<u8, _, _>")]`
+pub trait EnumIdInteger: Copy + Serialize + TryFrom<i64> + TryFrom<u64> {}
+
+impl EnumIdInteger for i8 {}
+impl EnumIdInteger for i16 {}
+impl EnumIdInteger for i32 {}
+impl EnumIdInteger for i64 {}
+impl EnumIdInteger for isize {}
+impl EnumIdInteger for u8 {}
+impl EnumIdInteger for u16 {}
+impl EnumIdInteger for u32 {}
+impl EnumIdInteger for u64 {}
+impl EnumIdInteger for usize {}
+
+#[derive(Debug, Clone, Copy)]
+enum RawId {
+    Signed(i64),
+    Unsigned(u64),
+}
+
+impl RawId {
+    fn from_scalar(scalar: &Scalar) -> Option<RawId> {
+        match scalar {
+            Scalar::UInt(u) => Some(RawId::Unsigned(*u)),
+            Scalar::Str(s) => match s.trim().parse() {
+                Ok(u) if i64::try_from(u).is_err() => Some(RawId::Unsigned(u)),
+                _ => scalar.as_i64().map(RawId::Signed),
+            },
+            _ => scalar.as_i64().map(RawId::Signed),
+        }
+    }
+
+    fn to_enum<I, T>(self) -> Option<T>
+    where
+        I: EnumIdInteger,
+        T: TryFrom<I>,
+    {
+        let id = match self {
+            RawId::Signed(i) => I::try_from(i).ok()?,
+            RawId::Unsigned(u) => I::try_from(u).ok()?,
+        };
+        T::try_from(id).ok()
+    }
+
+    fn unexpected(self) -> Unexpected<'static> {
+        match self {
+            RawId::Signed(i) => Unexpected::Signed(i),
+            RawId::Unsigned(u) => Unexpected::Unsigned(u),
+        }
+    }
+}
+
+fn raw_id<E: Error>(scalar: &Scalar) -> Result<RawId, E> {
+    RawId::from_scalar(scalar).ok_or_else(|| E::invalid_type(scalar.unexpected(), &"an integer id"))
+}
+
+// Ids out of range for `I` are reported the same way as ids `T` doesn't know
+fn from_scalar<I, T, E>(scalar: &Scalar) -> Result<T, E>
+where
+    I: EnumIdInteger,
+    T: TryFrom<I>,
+    E: Error,
+{
+    let id = raw_id(scalar)?;
+    id.to_enum::<I, T>()
+        .ok_or_else(|| E::invalid_value(id.unexpected(), &"a valid id"))
+}
+
+fn write<'a, I, S, T>(val: &'a T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    I: EnumIdInteger,
+    S: Serializer,
+    &'a T: Into<I>,
+{
+    val.into().serialize(serializer)
+}
+
+pub mod enum_from_id {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<i32>,
+    {
+        deserialize_as::<i32, D, T>(deserializer)
+    }
+
+    pub fn deserialize_as<'de, I, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        I: EnumIdInteger,
+        D: Deserializer<'de>,
+        T: TryFrom<I>,
+    {
+        from_scalar::<I, T, D::Error>(&Scalar::deserialize(deserializer)?)
+    }
+
+    pub fn serialize<'a, S, T>(val: &'a T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        &'a T: Into<i32>,
+    {
+        serialize_as::<i32, S, T>(val, serializer)
+    }
+
+    pub fn serialize_as<'a, I, S, T>(val: &'a T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        I: EnumIdInteger,
+        S: Serializer,
+        &'a T: Into<I>,
+    {
+        write::<I, S, T>(val, serializer)
+    }
+}
+
+pub mod enum_from_id_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<i32>,
+    {
+        deserialize_as::<i32, D, T>(deserializer)
+    }
+
+    // Anything that isn't a known id is read as `None`
+    pub fn deserialize_as<'de, I, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        I: EnumIdInteger,
+        D: Deserializer<'de>,
+        T: TryFrom<I>,
+    {
+        Ok(Scalar::deserialize(deserializer)
+            .ok()
+            .as_ref()
+            .and_then(RawId::from_scalar)
+            .and_then(RawId::to_enum::<I, T>))
+    }
+
+    pub fn deserialize_strict<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<i32>,
+    {
+        deserialize_strict_as::<i32, D, T>(deserializer)
+    }
+
+    pub fn deserialize_strict_as<'de, I, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        I: EnumIdInteger,
+        D: Deserializer<'de>,
+        T: TryFrom<I>,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            return Ok(None);
+        }
+
+        from_scalar::<I, T, D::Error>(&scalar).map(Some)
+    }
+
+    pub fn deserialize_audited<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<i32>,
+    {
+        deserialize_audited_as::<i32, D, T>(deserializer)
+    }
+
+    pub fn deserialize_audited_as<'de, I, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        I: EnumIdInteger,
+        D: Deserializer<'de>,
+        T: TryFrom<I>,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            return Ok(None);
+        }
+
+        let val = RawId::from_scalar(&scalar).and_then(RawId::to_enum::<I, T>);
+        if val.is_none() {
+            record_warning(format!(
+                "treating unknown id {:?} as null",
+                scalar.to_string()
+            ));
+        }
+        Ok(val)
+    }
+
+    pub fn serialize<'a, S, T>(val: &'a Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        &'a T: Into<i32>,
+    {
+        serialize_as::<i32, S, T>(val, serializer)
+    }
+
+    pub fn serialize_as<'a, I, S, T>(val: &'a Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        I: EnumIdInteger,
+        S: Serializer,
+        &'a T: Into<I>,
+    {
+        match val {
+            Some(val) => write::<I, S, T>(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn default<T>() -> Option<T>
+    where
+        T: Default,
+    {
+        Some(T::default())
+    }
+}
+
+pub mod enum_from_id_or_default {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<i32> + Default,
+    {
+        deserialize_as::<i32, D, T>(deserializer)
+    }
+
+    // Non-integer input is still an error; only unknown ids become the default
+    pub fn deserialize_as<'de, I, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        I: EnumIdInteger,
+        D: Deserializer<'de>,
+        T: TryFrom<I> + Default,
+    {
+        let id = raw_id::<D::Error>(&Scalar::deserialize(deserializer)?)?;
+        Ok(id.to_enum::<I, T>().unwrap_or_default())
+    }
+
+    pub fn serialize<'a, S, T>(val: &'a T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        &'a T: Into<i32>,
+    {
+        serialize_as::<i32, S, T>(val, serializer)
+    }
+
+    pub fn serialize_as<'a, I, S, T>(val: &'a T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        I: EnumIdInteger,
+        S: Serializer,
+        &'a T: Into<I>,
+    {
+        write::<I, S, T>(val, serializer)
+    }
+}
+
+fn coded_from_scalar<I, T, E>(scalar: Scalar) -> Result<CodedValue<T>, E>
+where
+    I: EnumIdInteger,
+    T: TryFrom<I>,
+    E: Error,
+{
+    if scalar.is_null() {
+        return Err(E::invalid_type(scalar.unexpected(), &"an integer id"));
+    }
+    Ok(
+        match RawId::from_scalar(&scalar).and_then(RawId::to_enum::<I, T>) {
+            Some(val) => CodedValue::Known(val),
+            None => unknown(raw_code(scalar)),
+        },
+    )
+}
+
+// Unknown ids are written back as the text they were read from, so "007" stays "007"
+fn write_coded<'a, I, S, T>(val: &'a CodedValue<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    I: EnumIdInteger,
+    S: Serializer,
+    &'a T: Into<I>,
+{
+    match val {
+        CodedValue::Known(val) => write::<I, S, T>(val, serializer),
+        CodedValue::Unknown(raw) => serializer.serialize_str(raw),
+    }
+}
+
+pub mod enum_from_id_or_unknown {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<CodedValue<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<i32>,
+    {
+        deserialize_as::<i32, D, T>(deserializer)
+    }
+
+    pub fn deserialize_as<'de, I, D, T>(deserializer: D) -> Result<CodedValue<T>, D::Error>
+    where
+        I: EnumIdInteger,
+        D: Deserializer<'de>,
+        T: TryFrom<I>,
+    {
+        coded_from_scalar::<I, T, D::Error>(Scalar::deserialize(deserializer)?)
+    }
+
+    pub fn serialize<'a, S, T>(val: &'a CodedValue<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        &'a T: Into<i32>,
+    {
+        serialize_as::<i32, S, T>(val, serializer)
+    }
+
+    pub fn serialize_as<'a, I, S, T>(
+        val: &'a CodedValue<T>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        I: EnumIdInteger,
+        S: Serializer,
+        &'a T: Into<I>,
+    {
+        write_coded::<I, S, T>(val, serializer)
+    }
+}
+
+pub mod enum_from_id_or_unknown_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<CodedValue<T>>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: TryFrom<i32>,
+    {
+        deserialize_as::<i32, D, T>(deserializer)
+    }
+
+    pub fn deserialize_as<'de, I, D, T>(deserializer: D) -> Result<Option<CodedValue<T>>, D::Error>
+    where
+        I: EnumIdInteger,
+        D: Deserializer<'de>,
+        T: TryFrom<I>,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            Ok(None)
+        } else {
+            coded_from_scalar::<I, T, D::Error>(scalar).map(Some)
+        }
+    }
+
+    pub fn serialize<'a, S, T>(
+        val: &'a Option<CodedValue<T>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        &'a T: Into<i32>,
+    {
+        serialize_as::<i32, S, T>(val, serializer)
+    }
+
+    pub fn serialize_as<'a, I, S, T>(
+        val: &'a Option<CodedValue<T>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        I: EnumIdInteger,
+        S: Serializer,
+        &'a T: Into<I>,
+    {
+        match val {
+            Some(val) => write_coded::<I, S, T>(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
@@ -0,0 +1,362 @@
+use crate::Scalar;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, Timelike, Utc};
+use serde::{de::Error, ser, Deserialize, Deserializer, Serializer};
+use std::convert::TryFrom;
+
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+const SECONDS_PER_DAY: f64 = 86_400.0;
+// 0001-01-01 to 1970-01-01 in 100ns ticks
+const DOTNET_TICKS_AT_UNIX_EPOCH: i64 = 621_355_968_000_000_000;
+const DOTNET_TICKS_PER_SECOND: i64 = 10_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampScale {
+    Unix { per_second: i64 },
+    DotNetTicks,
+    // Fractional days since 1899-12-30
+    OleAutomation,
+}
+
+impl TimestampScale {
+    pub const SECONDS: TimestampScale = TimestampScale::Unix { per_second: 1 };
+    pub const MILLIS: TimestampScale = TimestampScale::Unix { per_second: 1_000 };
+    pub const MICROS: TimestampScale = TimestampScale::Unix {
+        per_second: 1_000_000,
+    };
+    pub const NANOS: TimestampScale = TimestampScale::Unix {
+        per_second: NANOS_PER_SECOND,
+    };
+
+    pub fn from_i64(&self, n: i64) -> Option<NaiveDateTime> {
+        match *self {
+            TimestampScale::Unix { per_second } => from_unix_units(n, per_second),
+            TimestampScale::DotNetTicks => from_unix_units(
+                n.checked_sub(DOTNET_TICKS_AT_UNIX_EPOCH)?,
+                DOTNET_TICKS_PER_SECOND,
+            ),
+            TimestampScale::OleAutomation => self.from_f64(n as f64),
+        }
+    }
+
+    pub fn from_f64(&self, f: f64) -> Option<NaiveDateTime> {
+        if !f.is_finite() {
+            return None;
+        }
+        match *self {
+            TimestampScale::Unix { per_second } => {
+                let seconds = f / unix_units(per_second)? as f64;
+                let whole = seconds.floor();
+                let nanos = ((seconds - whole) * NANOS_PER_SECOND as f64).round() as i64;
+                from_unix_units(whole as i64, 1).map(|dt| dt + Duration::nanoseconds(nanos))
+            }
+            TimestampScale::DotNetTicks => {
+                if f.fract() == 0.0 && f.abs() < i64::MAX as f64 {
+                    self.from_i64(f as i64)
+                } else {
+                    None
+                }
+            }
+            // Negative values count days back, but the fraction is still the time of day
+            TimestampScale::OleAutomation => {
+                let days = f.trunc() as i64;
+                let millis = (f.fract().abs() * SECONDS_PER_DAY * 1000.0).round() as i64;
+                ole_base()
+                    .checked_add_signed(Duration::try_days(days)?)?
+                    .checked_add_signed(Duration::milliseconds(millis))
+            }
+        }
+    }
+
+    pub fn to_i64(&self, dt: &NaiveDateTime) -> Option<i64> {
+        match *self {
+            TimestampScale::Unix { per_second } => to_unix_units(dt, per_second),
+            TimestampScale::DotNetTicks => {
+                to_unix_units(dt, DOTNET_TICKS_PER_SECOND)?.checked_add(DOTNET_TICKS_AT_UNIX_EPOCH)
+            }
+            TimestampScale::OleAutomation => Some(self.to_f64(dt).round() as i64),
+        }
+    }
+
+    pub fn to_f64(&self, dt: &NaiveDateTime) -> f64 {
+        match *self {
+            TimestampScale::OleAutomation => {
+                let days = dt
+                    .date()
+                    .signed_duration_since(ole_base().date())
+                    .num_days() as f64;
+                let time = dt.time();
+                let fraction = (time.num_seconds_from_midnight() as f64
+                    + time.nanosecond() as f64 / NANOS_PER_SECOND as f64)
+                    / SECONDS_PER_DAY;
+                if days < 0.0 {
+                    days - fraction
+                } else {
+                    days + fraction
+                }
+            }
+            _ => self.to_i64(dt).map(|n| n as f64).unwrap_or(f64::NAN),
+        }
+    }
+
+    pub fn from_scalar(&self, scalar: &Scalar) -> Option<NaiveDateTime> {
+        match scalar {
+            Scalar::Int(i) => self.from_i64(*i),
+            Scalar::UInt(u) => self.from_i64(i64::try_from(*u).ok()?),
+            Scalar::Float(f) => self.from_f64(*f),
+            Scalar::Str(s) => {
+                let s = s.trim();
+                match s.parse::<i64>() {
+                    Ok(i) => self.from_i64(i),
+                    Err(_) => self.from_f64(s.parse().ok()?),
+                }
+            }
+            Scalar::Bool(_) | Scalar::Empty => None,
+        }
+    }
+}
+
+fn ole_base() -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(1899, 12, 30)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .expect("valid date")
+}
+
+// A `Unix` scale must divide a second into a whole number of nanoseconds, so a
+// `per_second` of 0, a negative one or one finer than nanoseconds has no timestamps
+fn unix_units(per_second: i64) -> Option<i64> {
+    if per_second > 0 && NANOS_PER_SECOND % per_second == 0 {
+        Some(per_second)
+    } else {
+        None
+    }
+}
+
+fn from_unix_units(n: i64, per_second: i64) -> Option<NaiveDateTime> {
+    let per_second = unix_units(per_second)?;
+    let seconds = n.div_euclid(per_second);
+    let nanos = n.rem_euclid(per_second) * (NANOS_PER_SECOND / per_second);
+    DateTime::from_timestamp(seconds, nanos as u32).map(|dt| dt.naive_utc())
+}
+
+fn to_unix_units(dt: &NaiveDateTime, per_second: i64) -> Option<i64> {
+    let per_second = unix_units(per_second)?;
+    let utc = dt.and_utc();
+    utc.timestamp()
+        .checked_mul(per_second)?
+        .checked_add(utc.timestamp_subsec_nanos() as i64 / (NANOS_PER_SECOND / per_second))
+}
+
+pub trait TimestampUnit {
+    const SCALE: TimestampScale;
+}
+
+pub struct UnixSeconds;
+
+impl TimestampUnit for UnixSeconds {
+    const SCALE: TimestampScale = TimestampScale::SECONDS;
+}
+
+pub struct UnixMillis;
+
+impl TimestampUnit for UnixMillis {
+    const SCALE: TimestampScale = TimestampScale::MILLIS;
+}
+
+pub struct UnixMicros;
+
+impl TimestampUnit for UnixMicros {
+    const SCALE: TimestampScale = TimestampScale::MICROS;
+}
+
+pub struct UnixNanos;
+
+impl TimestampUnit for UnixNanos {
+    const SCALE: TimestampScale = TimestampScale::NANOS;
+}
+
+pub struct DotNetTicks;
+
+impl TimestampUnit for DotNetTicks {
+    const SCALE: TimestampScale = TimestampScale::DotNetTicks;
+}
+
+pub struct OleAutomationDate;
+
+impl TimestampUnit for OleAutomationDate {
+    const SCALE: TimestampScale = TimestampScale::OleAutomation;
+}
+
+fn from_scalar<U: TimestampUnit, E: Error>(scalar: Scalar) -> Result<NaiveDateTime, E> {
+    match scalar {
+        Scalar::Bool(_) | Scalar::Empty => {
+            Err(E::invalid_type(scalar.unexpected(), &"a numeric timestamp"))
+        }
+        x => U::SCALE
+            .from_scalar(&x)
+            .ok_or_else(|| E::custom(format!("invalid timestamp: {}", x))),
+    }
+}
+
+fn write<U: TimestampUnit, S: Serializer>(
+    val: &NaiveDateTime,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match U::SCALE {
+        TimestampScale::OleAutomation => serializer.serialize_f64(U::SCALE.to_f64(val)),
+        scale => match scale.to_i64(val) {
+            Some(n) => serializer.serialize_i64(n),
+            None => Err(ser::Error::custom(format!(
+                "timestamp out of range: {}",
+                val
+            ))),
+        },
+    }
+}
+
+pub mod timestamp {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<NaiveDateTime, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_as::<UnixSeconds, D>(deserializer)
+    }
+
+    // e.g. `#[serde(deserialize_with = "timestamp::deserialize_as::<UnixMillis, _>")]`
+    pub fn deserialize_as<'de, U, D>(deserializer: D) -> Result<NaiveDateTime, D::Error>
+    where
+        U: TimestampUnit,
+        D: Deserializer<'de>,
+    {
+        from_scalar::<U, D::Error>(Scalar::deserialize(deserializer)?)
+    }
+
+    pub fn serialize<S>(val: &NaiveDateTime, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_as::<UnixSeconds, S>(val, serializer)
+    }
+
+    pub fn serialize_as<U, S>(val: &NaiveDateTime, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        U: TimestampUnit,
+        S: Serializer,
+    {
+        write::<U, S>(val, serializer)
+    }
+}
+
+pub mod timestamp_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<NaiveDateTime>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_as::<UnixSeconds, D>(deserializer)
+    }
+
+    pub fn deserialize_as<'de, U, D>(deserializer: D) -> Result<Option<NaiveDateTime>, D::Error>
+    where
+        U: TimestampUnit,
+        D: Deserializer<'de>,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            Ok(None)
+        } else {
+            from_scalar::<U, D::Error>(scalar).map(Some)
+        }
+    }
+
+    pub fn serialize<S>(val: &Option<NaiveDateTime>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_as::<UnixSeconds, S>(val, serializer)
+    }
+
+    pub fn serialize_as<U, S>(val: &Option<NaiveDateTime>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        U: TimestampUnit,
+        S: Serializer,
+    {
+        match val {
+            Some(val) => write::<U, S>(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+pub mod timestamp_utc {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_as::<UnixSeconds, D>(deserializer)
+    }
+
+    pub fn deserialize_as<'de, U, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        U: TimestampUnit,
+        D: Deserializer<'de>,
+    {
+        timestamp::deserialize_as::<U, D>(deserializer).map(|dt| dt.and_utc())
+    }
+
+    pub fn serialize<S>(val: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_as::<UnixSeconds, S>(val, serializer)
+    }
+
+    pub fn serialize_as<U, S>(val: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        U: TimestampUnit,
+        S: Serializer,
+    {
+        write::<U, S>(&val.naive_utc(), serializer)
+    }
+}
+
+pub mod timestamp_utc_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_as::<UnixSeconds, D>(deserializer)
+    }
+
+    pub fn deserialize_as<'de, U, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where
+        U: TimestampUnit,
+        D: Deserializer<'de>,
+    {
+        timestamp_opt::deserialize_as::<U, D>(deserializer).map(|dt| dt.map(|dt| dt.and_utc()))
+    }
+
+    pub fn serialize<S>(val: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_as::<UnixSeconds, S>(val, serializer)
+    }
+
+    pub fn serialize_as<U, S>(val: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        U: TimestampUnit,
+        S: Serializer,
+    {
+        match val {
+            Some(val) => write::<U, S>(&val.naive_utc(), serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
@@ -1,3 +1,11 @@
+use crate::{
+    columns::Tracked,
+    dates::NUM_DAYS_1900_01_01_FROM_CE,
+    detect::{detect_columns, transpose},
+    is_null_token,
+    load::{detection_enabled, load_rows},
+    with_typed_cells,
+};
 use calamine::{open_workbook, DataType, Reader, Xlsx};
 use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use serde::{
@@ -37,23 +45,32 @@ pub trait FromXlsx {
             .worksheet_range_at(0)
             .ok_or(calamine::Error::Msg("sheet not found"))??;
 
-        Ok(range
-            .deserialize()?
-            .filter_map(|result| {
-                result
-                    .map_err(|e| {
-                        eprintln!("failed deserializing record: {:?}", e);
-                        e
+        // Native date cells need no detection, so only text cells are scanned. Headers are
+        // named as calamine reads them, so fields find their columns.
+        if detection_enabled() {
+            let headers = range
+                .rows()
+                .next()
+                .map(|row| row.iter().map(DataType::to_string).collect())
+                .unwrap_or_default();
+            detect_columns(
+                headers,
+                transpose(range.rows().skip(1).map(|row| {
+                    row.iter().map(|cell| match cell {
+                        DataType::String(s) => s.clone(),
+                        _ => String::new(),
                     })
-                    .ok()
-            })
-            .collect())
+                })),
+            );
+        }
+
+        let rows = range
+            .deserialize::<Tracked<Self>>()?
+            .map(|row| row.map(Tracked::into_inner));
+        Ok(with_typed_cells(|| load_rows(rows)))
     }
 }
 
-// Excel apparently considers 1900 to be a leap year
-const NUM_DAYS_1900_01_01_FROM_CE: i32 = 693594;
-
 pub mod excel_date {
     use super::*;
 
@@ -85,7 +102,7 @@ pub mod excel_date_opt {
         let data_type = DataType::deserialize(deserializer)?;
         match data_type {
             DataType::String(s) => {
-                if s.is_empty() {
+                if is_null_token(&s) {
                     Ok(None)
                 } else {
                     Err(Error::custom(format!("invalid date: {:?}", s)))
@@ -138,7 +155,7 @@ pub mod excel_datetime_opt {
         let data_type = DataType::deserialize(deserializer)?;
         match data_type {
             DataType::String(s) => {
-                if s.is_empty() {
+                if is_null_token(&s) {
                     Ok(None)
                 } else {
                     Err(Error::custom(format!("invalid datetime: {:?}", s)))
@@ -195,7 +212,7 @@ pub mod excel_time_opt {
         let data_type = DataType::deserialize(deserializer)?;
         match data_type {
             DataType::String(s) => {
-                if s.is_empty() {
+                if is_null_token(&s) {
                     Ok(None)
                 } else {
                     Ok(Some(NaiveTime::parse_from_str(&s, TIME_FORMAT).map_err(
@@ -0,0 +1,205 @@
+use crate::{CharClass, Scalar};
+use serde::{de::Error, ser, Deserialize, Deserializer, Serializer};
+
+// Largest integer an f64 holds exactly
+const MAX_EXACT_FLOAT: f64 = 9_007_199_254_740_992.0;
+
+// How an identifier is written canonically, so the same id read from CSV text,
+// an integer cell or an Excel float compares equal
+pub trait IdentifierFormat {
+    // Removed wherever they appear, e.g. the dashes in "123-45-67"
+    const STRIP: &'static [char] = &['-', ' ', '/', '.'];
+    const CHARACTERS: CharClass = CharClass::Digits;
+    const UPPERCASE: bool = true;
+    // Drops leading zeros before padding, so "0012" and 12 are the same id
+    const TRIM_ZEROS: bool = true;
+    // Left-pads to this many characters; longer ids are an error
+    const WIDTH: Option<usize> = None;
+    const PAD: char = '0';
+}
+
+// Digits only, without leading zeros
+pub struct NumericId;
+
+impl IdentifierFormat for NumericId {}
+
+// Digits only, zero-padded to `WIDTH`,
+// e.g. `#[serde(deserialize_with = "identifier::deserialize_as::<PaddedId<8>, _>")]`
+pub struct PaddedId<const WIDTH: usize>;
+
+impl<const WIDTH: usize> IdentifierFormat for PaddedId<WIDTH> {
+    const WIDTH: Option<usize> = Some(WIDTH);
+}
+
+// Uppercase ASCII letters and digits, leading zeros kept
+pub struct AlphanumericId;
+
+impl IdentifierFormat for AlphanumericId {
+    const CHARACTERS: CharClass = CharClass::AsciiAlphanumeric;
+    const TRIM_ZEROS: bool = false;
+}
+
+fn float_text(f: f64) -> Option<String> {
+    if f.is_finite() && f.fract() == 0.0 && f.abs() <= MAX_EXACT_FLOAT {
+        Some(format!("{}", f as i64))
+    } else {
+        None
+    }
+}
+
+fn is_digits(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_digit())
+}
+
+// Undoes spreadsheet formatting of numeric ids, e.g. "1234567.0". Any other decimal,
+// such as "123.45", is an error rather than the id "12345".
+fn unfloat(s: &str) -> Result<&str, &'static str> {
+    match s.split_once('.') {
+        Some((whole, fraction)) if is_digits(whole) && is_digits(fraction) => {
+            if fraction.chars().all(|c| c == '0') {
+                Ok(whole)
+            } else {
+                Err("has a decimal part")
+            }
+        }
+        _ => Ok(s),
+    }
+}
+
+pub fn normalize_identifier<F: IdentifierFormat>(s: &str) -> Result<String, String> {
+    let trimmed = s.trim();
+    if trimmed.starts_with(['-', '+']) {
+        return Err(format!("invalid identifier {:?}: has a sign", s));
+    }
+    let text = unfloat(trimmed).map_err(|e| format!("invalid identifier {:?}: {}", s, e))?;
+
+    let mut id: String = text.chars().filter(|c| !F::STRIP.contains(c)).collect();
+    if F::UPPERCASE {
+        id = id.to_uppercase();
+    }
+    if let Some(c) = id.chars().find(|&c| !F::CHARACTERS.allows(c)) {
+        return Err(format!(
+            "invalid identifier {:?}: contains {:?}; expected only {}",
+            s,
+            c,
+            F::CHARACTERS
+        ));
+    }
+    if id.is_empty() {
+        return Err(format!("invalid identifier {:?}: empty", s));
+    }
+    if F::TRIM_ZEROS {
+        // An id of all zeros keeps one
+        let trimmed = id.trim_start_matches('0');
+        id = if trimmed.is_empty() { "0" } else { trimmed }.to_string();
+    }
+
+    if let Some(width) = F::WIDTH {
+        let length = id.chars().count();
+        if length > width {
+            return Err(format!(
+                "invalid identifier {:?}: longer than {} characters",
+                s, width
+            ));
+        }
+        id.insert_str(0, &F::PAD.to_string().repeat(width - length));
+    }
+
+    Ok(id)
+}
+
+// Text is normalized as written; typed float cells are whole numbers as Excel stores ids
+fn from_scalar<F: IdentifierFormat, E: Error>(scalar: Scalar) -> Result<String, E> {
+    let text = match scalar {
+        Scalar::Int(i) => i.to_string(),
+        Scalar::UInt(u) => u.to_string(),
+        Scalar::Float(f) => {
+            float_text(f).ok_or_else(|| E::custom(format!("invalid identifier: {}", f)))?
+        }
+        Scalar::Str(s) => s,
+        x => return Err(E::invalid_type(x.unexpected(), &"an identifier")),
+    };
+    normalize_identifier::<F>(&text).map_err(E::custom)
+}
+
+fn write<F: IdentifierFormat, S: Serializer>(val: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    let id = normalize_identifier::<F>(val).map_err(ser::Error::custom)?;
+    serializer.serialize_str(&id)
+}
+
+pub mod identifier {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_as::<NumericId, D>(deserializer)
+    }
+
+    pub fn deserialize_as<'de, F, D>(deserializer: D) -> Result<String, D::Error>
+    where
+        F: IdentifierFormat,
+        D: Deserializer<'de>,
+    {
+        from_scalar::<F, D::Error>(Scalar::deserialize(deserializer)?)
+    }
+
+    pub fn serialize<S>(val: &str, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_as::<NumericId, S>(val, serializer)
+    }
+
+    // Always writes text, so padded ids keep their leading zeros
+    pub fn serialize_as<F, S>(val: &str, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        F: IdentifierFormat,
+        S: Serializer,
+    {
+        write::<F, S>(val, serializer)
+    }
+}
+
+pub mod identifier_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_as::<NumericId, D>(deserializer)
+    }
+
+    pub fn deserialize_as<'de, F, D>(deserializer: D) -> Result<Option<String>, D::Error>
+    where
+        F: IdentifierFormat,
+        D: Deserializer<'de>,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            Ok(None)
+        } else {
+            from_scalar::<F, D::Error>(scalar).map(Some)
+        }
+    }
+
+    pub fn serialize<S>(val: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serialize_as::<NumericId, S>(val, serializer)
+    }
+
+    pub fn serialize_as<F, S>(val: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        F: IdentifierFormat,
+        S: Serializer,
+    {
+        match val {
+            Some(val) => write::<F, S>(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
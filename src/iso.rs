@@ -0,0 +1,309 @@
+use crate::{is_null_token, PartialDate, Scalar};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+const DATE_FORMATS: &[&str] = &["%Y-%m-%d", "%Y%m%d"];
+const DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y%m%dT%H%M%S%.f",
+    "%Y%m%dT%H%M",
+];
+const OFFSET_DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f%#z",
+    "%Y-%m-%dT%H:%M%#z",
+    "%Y%m%dT%H%M%S%.f%#z",
+    "%Y%m%dT%H%M%#z",
+];
+
+const DATE_FORMAT: &str = "%Y-%m-%d";
+const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.f";
+
+// Accepts a space or lowercase 't' between date and time, and 'Z' for UTC
+fn normalize(s: &str) -> String {
+    let mut normalized: String = s.trim().to_string();
+    if let Some(i) = normalized.find([' ', 't']) {
+        if normalized[..i]
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '-')
+        {
+            normalized.replace_range(i..=i, "T");
+        }
+    }
+    if normalized.ends_with(['Z', 'z']) {
+        normalized.pop();
+        normalized.push_str("+00:00");
+    }
+    normalized
+}
+
+fn has_offset(s: &str) -> bool {
+    match s.find('T') {
+        Some(i) => s[i..].contains(['+', '-']),
+        None => false,
+    }
+}
+
+pub fn parse_iso8601_date(s: &str) -> Option<NaiveDate> {
+    let s = s.trim();
+    DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(s, format).ok())
+}
+
+// Rejects values with an offset rather than silently dropping it
+pub fn parse_iso8601_datetime(s: &str) -> Option<NaiveDateTime> {
+    let s = normalize(s);
+    if has_offset(&s) {
+        return None;
+    }
+    DATETIME_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&s, format).ok())
+}
+
+pub fn parse_iso8601_offset_datetime(s: &str) -> Option<DateTime<FixedOffset>> {
+    let s = normalize(s);
+    OFFSET_DATETIME_FORMATS
+        .iter()
+        .find_map(|format| DateTime::parse_from_str(&s, format).ok())
+}
+
+fn deserialize_str<'de, D, T, P>(deserializer: D, parse: P, what: &str) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    P: FnOnce(&str) -> Option<T>,
+{
+    let s = String::deserialize(deserializer)?;
+    parse(&s).ok_or_else(|| D::Error::custom(format!("invalid {}: {}", what, s)))
+}
+
+fn deserialize_str_opt<'de, D, T, P>(
+    deserializer: D,
+    parse: P,
+    what: &str,
+) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    P: FnOnce(&str) -> Option<T>,
+{
+    let s = String::deserialize(deserializer)?;
+    if is_null_token(&s) {
+        Ok(None)
+    } else {
+        parse(&s)
+            .map(Some)
+            .ok_or_else(|| D::Error::custom(format!("invalid {}: {}", what, s)))
+    }
+}
+
+pub mod iso8601_date {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_str(deserializer, parse_iso8601_date, "date")
+    }
+
+    pub fn serialize<S>(val: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&val.format(DATE_FORMAT).to_string())
+    }
+}
+
+pub mod iso8601_date_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_str_opt(deserializer, parse_iso8601_date, "date")
+    }
+
+    pub fn serialize<S>(val: &Option<NaiveDate>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => iso8601_date::serialize(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+pub mod iso8601_datetime {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<NaiveDateTime, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_str(deserializer, parse_iso8601_datetime, "datetime")
+    }
+
+    pub fn serialize<S>(val: &NaiveDateTime, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&val.format(DATETIME_FORMAT).to_string())
+    }
+}
+
+pub mod iso8601_datetime_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<NaiveDateTime>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_str_opt(deserializer, parse_iso8601_datetime, "datetime")
+    }
+
+    pub fn serialize<S>(val: &Option<NaiveDateTime>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => iso8601_datetime::serialize(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+pub mod iso8601_offset_datetime {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<FixedOffset>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_str(deserializer, parse_iso8601_offset_datetime, "datetime")
+    }
+
+    // RFC 3339, e.g. "2021-03-14T07:30:00.123-05:00"
+    pub fn serialize<S>(val: &DateTime<FixedOffset>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&val.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    }
+}
+
+pub mod iso8601_offset_datetime_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<FixedOffset>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_str_opt(deserializer, parse_iso8601_offset_datetime, "datetime")
+    }
+
+    pub fn serialize<S>(
+        val: &Option<DateTime<FixedOffset>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => iso8601_offset_datetime::serialize(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+pub mod iso8601_utc_datetime {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        iso8601_offset_datetime::deserialize(deserializer).map(|dt| dt.with_timezone(&Utc))
+    }
+
+    // RFC 3339 with a "Z" suffix, e.g. "2021-03-14T12:30:00.123Z"
+    pub fn serialize<S>(val: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&val.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    }
+}
+
+pub mod iso8601_utc_datetime_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        iso8601_offset_datetime_opt::deserialize(deserializer)
+            .map(|dt| dt.map(|dt| dt.with_timezone(&Utc)))
+    }
+
+    pub fn serialize<S>(val: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => iso8601_utc_datetime::serialize(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+// Year-only values arrive as numbers from CSV and Excel, so these read any scalar
+pub mod iso8601_partial_date {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<PartialDate, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = Scalar::deserialize(deserializer)?.to_string();
+        PartialDate::parse_iso8601(&s)
+            .ok_or_else(|| D::Error::custom(format!("invalid partial date: {}", s)))
+    }
+
+    pub fn serialize<S>(val: &PartialDate, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&val.to_string())
+    }
+}
+
+pub mod iso8601_partial_date_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<PartialDate>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            return Ok(None);
+        }
+        let s = scalar.to_string();
+        PartialDate::parse_iso8601(&s)
+            .map(Some)
+            .ok_or_else(|| D::Error::custom(format!("invalid partial date: {}", s)))
+    }
+
+    pub fn serialize<S>(val: &Option<PartialDate>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => iso8601_partial_date::serialize(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
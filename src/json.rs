@@ -0,0 +1,69 @@
+use crate::{is_null_token, with_typed_cells};
+use serde::{de::Error, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+fn from_str<T, E>(s: &str) -> Result<T, E>
+where
+    T: for<'a> Deserialize<'a>,
+    E: Error,
+{
+    with_typed_cells(|| serde_json::from_str(s))
+        .map_err(|err| E::custom(format!("invalid embedded JSON: {}", err)))
+}
+
+fn write<T, S>(val: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    let json = serde_json::to_string(val).map_err(ser::Error::custom)?;
+    serializer.serialize_str(&json)
+}
+
+// Reads a cell holding a JSON document into any `T`, and writes it back as compact JSON
+pub mod embedded_json {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: for<'a> Deserialize<'a>,
+    {
+        from_str(&String::deserialize(deserializer)?)
+    }
+
+    pub fn serialize<S, T>(val: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        write(val, serializer)
+    }
+}
+
+pub mod embedded_json_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: for<'a> Deserialize<'a>,
+    {
+        let s = String::deserialize(deserializer)?;
+        if is_null_token(&s) {
+            Ok(None)
+        } else {
+            from_str(&s).map(Some)
+        }
+    }
+
+    pub fn serialize<S, T>(val: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        match val {
+            Some(val) => write(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
@@ -1,63 +1,135 @@
+use crate::{
+    columns::Tracked,
+    detect::{detect_columns, transpose},
+    load::{detection_enabled, load_csv, load_rows},
+};
 use csv::StringRecord;
 use serde::{de::DeserializeOwned, Deserialize, Deserializer};
 
 use std::{io::Read, path::Path};
 
+mod booleans;
+mod codes;
+mod columns;
+mod dates;
+mod detect;
+mod elapsed;
+mod enum_ids;
+mod epoch;
+mod identifiers;
+mod iso;
+mod lists;
+mod load;
+mod lookups;
+mod null;
+mod numeric;
+mod pairs;
+mod partial;
+mod quantity;
+mod scalar;
+mod strings;
+mod validated;
+mod years;
+
+pub use booleans::*;
+pub use codes::*;
+pub use detect::*;
+pub use elapsed::*;
+pub use enum_ids::*;
+pub use epoch::*;
+pub use identifiers::*;
+pub use iso::*;
+pub use lists::*;
+pub use load::*;
+pub use lookups::*;
+pub use null::*;
+pub use numeric::*;
+pub use pairs::*;
+pub use partial::*;
+pub use quantity::*;
+pub use scalar::*;
+pub use strings::*;
+pub use validated::*;
+pub use years::*;
+
 #[cfg(feature = "calamine")]
 mod excel;
 
 #[cfg(feature = "calamine")]
 pub use excel::*;
 
+#[cfg(feature = "rust_decimal")]
+mod decimal;
+
+#[cfg(feature = "rust_decimal")]
+pub use decimal::*;
+
+#[cfg(feature = "serde_json")]
+mod json;
+
+#[cfg(feature = "serde_json")]
+pub use json::*;
+
+#[cfg(feature = "chrono-tz")]
+mod zoned;
+
+#[cfg(feature = "chrono-tz")]
+pub use zoned::*;
+
 pub trait FromCsv {
     fn from_csv_reader<R>(reader: R) -> Result<Vec<Self>, csv::Error>
     where
         Self: Sized + DeserializeOwned,
         R: Read,
     {
-        let mut rdr = csv::Reader::from_reader(reader);
-        Ok(rdr
-            .deserialize()
-            .filter_map(|r| {
-                r.map_err(|err| {
-                    eprintln!("Failed deserializing record: {:?}", &err);
-                    err
-                })
-                .ok()
-            })
-            .collect())
+        load_csv(csv::Reader::from_reader(reader))
     }
 
     fn from_bytes(bytes: &[u8]) -> Result<Vec<Self>, csv::Error>
     where
         Self: Sized + DeserializeOwned + std::fmt::Debug,
     {
+        if detection_enabled() {
+            let mut scan = csv::Reader::from_reader(bytes);
+            let headers = scan
+                .byte_headers()
+                .map(|headers| {
+                    StringRecord::from_byte_record_lossy(headers.clone())
+                        .iter()
+                        .map(String::from)
+                        .collect()
+                })
+                .unwrap_or_default();
+            detect_columns(
+                headers,
+                transpose(scan.byte_records().filter_map(Result::ok).map(|record| {
+                    StringRecord::from_byte_record_lossy(record)
+                        .iter()
+                        .map(String::from)
+                        .collect::<Vec<_>>()
+                })),
+            );
+        }
+
         let mut rdr = csv::Reader::from_reader(bytes);
         let byte_headers = rdr.byte_headers().ok().cloned();
         let string_headers = byte_headers
             .clone()
-            .map(|h| StringRecord::from_byte_record(h).ok())
-            .flatten();
-        Ok(rdr
-            .byte_records()
-            .filter_map(|byte_record_r| {
-                byte_record_r
-                    .and_then(|byte_record| {
-                        byte_record
-                            .deserialize(byte_headers.as_ref())
-                            .or_else(|err| {
-                                eprintln!(
-                                    "Failed deserializing record, attempting lossy: {:?}",
-                                    &err
-                                );
-
-                                StringRecord::from_byte_record_lossy(byte_record)
-                                    .deserialize(string_headers.as_ref())
-                            })
+            .and_then(|h| StringRecord::from_byte_record(h).ok());
+        let rows = rdr.byte_records().map(|byte_record_r| {
+            byte_record_r.and_then(|byte_record| {
+                byte_record
+                    .deserialize(byte_headers.as_ref())
+                    .or_else(|err| {
+                        eprintln!("Failed deserializing record, attempting lossy: {:?}", &err);
+
+                        StringRecord::from_byte_record_lossy(byte_record)
+                            .deserialize(string_headers.as_ref())
                     })
-                    .ok()
+                    .map(Tracked::into_inner)
             })
-            .collect())
+        });
+        Ok(with_text_cells(|| load_rows(rows)))
     }
 
     fn from_csv<P>(path: P) -> Result<Vec<Self>, csv::Error>
@@ -65,17 +137,7 @@ pub trait FromCsv {
         Self: Sized + DeserializeOwned,
         P: AsRef<Path>,
     {
-        let mut rdr = csv::Reader::from_path(path)?;
-        Ok(rdr
-            .deserialize()
-            .filter_map(|r| {
-                r.map_err(|err| {
-                    eprintln!("Failed deserializing record: {:?}", &err);
-                    err
-                })
-                .ok()
-            })
-            .collect())
+        load_csv(csv::Reader::from_path(path)?)
     }
 
     fn from_tsv_reader<R>(reader: R) -> Result<Vec<Self>, csv::Error>
@@ -83,34 +145,29 @@ pub trait FromCsv {
         Self: Sized + DeserializeOwned,
         R: Read,
     {
-        let mut rdr = csv::ReaderBuilder::new()
-            .delimiter(b'\t')
-            .from_reader(reader);
-        Ok(rdr
-            .deserialize()
-            .filter_map(|r| {
-                r.map_err(|err| {
-                    eprintln!("Failed deserializing record: {:?}", &err);
-                    err
-                })
-                .ok()
-            })
-            .collect())
+        load_csv(
+            csv::ReaderBuilder::new()
+                .delimiter(b'\t')
+                .from_reader(reader),
+        )
     }
 }
 
 pub mod zero_one_bool {
+    use crate::Scalar;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<bool, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        match s.as_ref() {
-            "1" | "true" => Ok(true),
-            "0" | "false" => Ok(false),
-            _ => Err(serde::de::Error::custom("Not one or zero")),
+        match Scalar::deserialize(deserializer)? {
+            Scalar::Bool(b) => Ok(b),
+            x => match x.to_string().as_ref() {
+                "1" | "true" => Ok(true),
+                "0" | "false" => Ok(false),
+                _ => Err(serde::de::Error::custom("Not one or zero")),
+            },
         }
     }
 
@@ -126,17 +183,20 @@ pub mod zero_one_bool {
 }
 
 pub mod yes_no_bool {
+    use crate::Scalar;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<bool, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        match s.as_ref() {
-            "Yes" | "Y" => Ok(true),
-            "No" | "N" => Ok(false),
-            _ => Err(serde::de::Error::custom("Not yes or no")),
+        match Scalar::deserialize(deserializer)? {
+            Scalar::Bool(b) => Ok(b),
+            x => match x.to_string().as_ref() {
+                "Yes" | "Y" => Ok(true),
+                "No" | "N" => Ok(false),
+                _ => Err(serde::de::Error::custom("Not yes or no")),
+            },
         }
     }
 
@@ -152,18 +212,21 @@ pub mod yes_no_bool {
 }
 
 pub mod nullable_yes_no_bool {
+    use crate::{is_null_token, Scalar};
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        match s.as_ref() {
-            "Yes" => Ok(Some(true)),
-            "No" => Ok(Some(false)),
-            "" | "NA" => Ok(None),
-            _ => Err(serde::de::Error::custom("Not yes or no")),
+        match Scalar::deserialize(deserializer)? {
+            Scalar::Bool(b) => Ok(Some(b)),
+            x => match x.to_string().as_ref() {
+                "Yes" => Ok(Some(true)),
+                "No" => Ok(Some(false)),
+                s if is_null_token(s) => Ok(None),
+                _ => Err(serde::de::Error::custom("Not yes or no")),
+            },
         }
     }
 
@@ -180,17 +243,20 @@ pub mod nullable_yes_no_bool {
 }
 
 pub mod true_false_bool {
+    use crate::Scalar;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<bool, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        match s.as_ref() {
-            "True" | "true" => Ok(true),
-            "False" | "false" => Ok(false),
-            _ => Err(serde::de::Error::custom("Not true or false")),
+        match Scalar::deserialize(deserializer)? {
+            Scalar::Bool(b) => Ok(b),
+            x => match x.to_string().as_ref() {
+                "True" | "true" => Ok(true),
+                "False" | "false" => Ok(false),
+                _ => Err(serde::de::Error::custom("Not true or false")),
+            },
         }
     }
 
@@ -206,18 +272,21 @@ pub mod true_false_bool {
 }
 
 pub mod nullable_true_false_bool {
+    use crate::{is_null_token, Scalar};
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        match s.as_ref() {
-            "1" | "true" | "True" => Ok(Some(true)),
-            "0" | "false" | "False" => Ok(Some(false)),
-            "" | "NA" => Ok(None),
-            _ => Err(serde::de::Error::custom("Not true or false or empty/NA")),
+        match Scalar::deserialize(deserializer)? {
+            Scalar::Bool(b) => Ok(Some(b)),
+            x => match x.to_string().as_ref() {
+                "1" | "true" | "True" => Ok(Some(true)),
+                "0" | "false" | "False" => Ok(Some(false)),
+                s if is_null_token(s) => Ok(None),
+                _ => Err(serde::de::Error::custom("Not true or false or null")),
+            },
         }
     }
 
@@ -234,16 +303,19 @@ pub mod nullable_true_false_bool {
 }
 
 pub mod non_null_bool {
+    use crate::Scalar;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<bool, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        match s.as_ref() {
-            "" | "NULL" | "0" => Ok(false),
-            _ => Ok(true),
+        match Scalar::deserialize(deserializer)? {
+            Scalar::Bool(b) => Ok(b),
+            x => match x.to_string().as_ref() {
+                "" | "NULL" | "0" => Ok(false),
+                _ => Ok(true),
+            },
         }
     }
 
@@ -259,17 +331,20 @@ pub mod non_null_bool {
 }
 
 pub mod zero_one_int_bool {
+    use crate::Scalar;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<bool, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let i = i32::deserialize(deserializer)?;
-        match i {
-            1 => Ok(true),
-            0 => Ok(false),
-            _ => Err(serde::de::Error::custom("Not one or zero")),
+        match Scalar::deserialize(deserializer)? {
+            Scalar::Bool(b) => Ok(b),
+            x => match x.as_i64() {
+                Some(1) => Ok(true),
+                Some(0) => Ok(false),
+                _ => Err(serde::de::Error::custom("Not one or zero")),
+            },
         }
     }
 
@@ -285,17 +360,20 @@ pub mod zero_one_int_bool {
 }
 
 pub mod nullable_bool {
+    use crate::Scalar;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        Ok(match s.as_ref() {
-            "1" | "true" => Some(true),
-            "0" | "false" => Some(false),
-            _ => None,
+        Ok(match Scalar::deserialize(deserializer)? {
+            Scalar::Bool(b) => Some(b),
+            x => match x.to_string().as_ref() {
+                "1" | "true" => Some(true),
+                "0" | "false" => Some(false),
+                _ => None,
+            },
         })
     }
 
@@ -319,17 +397,21 @@ pub mod nullable_bool {
 }
 
 pub mod nullable_int_bool {
+    use crate::Scalar;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<bool>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        i32::deserialize(deserializer)
-            .map(|i| match i {
-                1 => Some(true),
-                0 => Some(false),
-                _ => None,
+        Scalar::deserialize(deserializer)
+            .map(|x| match x {
+                Scalar::Bool(b) => Some(b),
+                x => match x.as_i64() {
+                    Some(1) => Some(true),
+                    Some(0) => Some(false),
+                    _ => None,
+                },
             })
             .or(Ok(None))
     }
@@ -370,6 +452,7 @@ pub mod nonempty_string {
 }
 
 pub mod nullable_string {
+    use crate::is_null_token;
     use serde::{self, Deserialize, Deserializer};
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
@@ -377,10 +460,11 @@ pub mod nullable_string {
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        Ok(match s.as_ref() {
-            "NULL" | "" => None,
-            x => Some(x.to_string()),
-        })
+        if is_null_token(&s) {
+            Ok(None)
+        } else {
+            Ok(Some(s))
+        }
     }
 }
 
@@ -396,6 +480,7 @@ pub mod semi_separated_list {
         Ok(s.split(';').map(|s| s.to_owned()).collect())
     }
 
+    #[allow(clippy::ptr_arg)]
     pub fn serialize<S>(val: &Vec<String>, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -405,21 +490,34 @@ pub mod semi_separated_list {
 }
 
 pub mod mm_dd_yy_date {
+    use crate::{parse_mm_dd_yy, years::current_year_pivot, YearPivot, YearWindow};
     use chrono::NaiveDate;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     const FORMAT: &str = "%m/%d/%y";
-    const ALT_FORMAT: &str = "%m/%d/%Y";
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
     where
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
+        parse(&s, current_year_pivot())
+    }
+
+    // e.g. `#[serde(deserialize_with = "mm_dd_yy_date::deserialize_as::<SlidingPivot<10>, _>")]`
+    pub fn deserialize_as<'de, W, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+    where
+        W: YearWindow,
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        parse(&s, W::PIVOT)
+    }
+
+    fn parse<E: serde::de::Error>(s: &str, pivot: YearPivot) -> Result<NaiveDate, E> {
         let trimmed = s.trim();
-        NaiveDate::parse_from_str(trimmed, FORMAT)
-            .or_else(|_| NaiveDate::parse_from_str(trimmed, ALT_FORMAT))
-            .map_err(|e| serde::de::Error::custom(format!("invalid date: {} {:?}", trimmed, e)))
+        parse_mm_dd_yy(trimmed, pivot)
+            .ok_or_else(|| E::custom(format!("invalid date: {}", trimmed)))
     }
 
     pub fn serialize<S>(val: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error>
@@ -455,23 +553,42 @@ pub mod timeless_mm_dd_yyyy_date {
 }
 
 pub mod mm_dd_yyyy_date {
+    use crate::dates::{
+        parse_date_or_datetime, MM_DD_YYYY_DATETIME_FORMATS, MM_DD_YYYY_DATE_FORMATS,
+    };
     use chrono::NaiveDate;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
-    const FORMAT: &str = "%m/%d/%Y %H:%M:%S";
-    const ALT_FORMAT: &str = "%m/%d/%Y %H:%M";
-    const OTHER_ALT_FORMAT: &str = "%m/%d/%y %H:%M";
+    const FORMAT: &str = "%m/%d/%Y";
 
+    // Accepts "03/14/2021" as well as "03/14/2021 07:30:00", dropping the time
     pub fn deserialize<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
     where
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        let trimmed = s.trim();
-        NaiveDate::parse_from_str(trimmed, FORMAT)
-            .or_else(|_| NaiveDate::parse_from_str(trimmed, ALT_FORMAT))
-            .or_else(|_| NaiveDate::parse_from_str(trimmed, OTHER_ALT_FORMAT))
-            .map_err(|e| serde::de::Error::custom(format!("invalid date: {} {:?}", trimmed, e)))
+        parse_date_or_datetime(
+            &s,
+            MM_DD_YYYY_DATE_FORMATS,
+            MM_DD_YYYY_DATETIME_FORMATS,
+            false,
+        )
+        .map_err(serde::de::Error::custom)
+    }
+
+    // Like `deserialize`, but fails if a time other than midnight is present
+    pub fn deserialize_strict<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        parse_date_or_datetime(
+            &s,
+            MM_DD_YYYY_DATE_FORMATS,
+            MM_DD_YYYY_DATETIME_FORMATS,
+            true,
+        )
+        .map_err(serde::de::Error::custom)
     }
 
     pub fn serialize<S>(val: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error>
@@ -512,30 +629,56 @@ pub mod mm_dd_yyyy_datetime {
 }
 
 pub mod mm_dd_yyyy_date_opt {
+    use crate::{
+        dates::{parse_date_or_datetime, MM_DD_YYYY_DATETIME_FORMATS, MM_DD_YYYY_DATE_FORMATS},
+        is_null_token,
+    };
     use chrono::NaiveDate;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
-    const FORMAT: &str = "%m/%d/%Y %H:%M:%S";
-    const ALT_FORMAT: &str = "%m/%d/%Y %H:%M";
-    const OTHER_ALT_FORMAT: &str = "%m/%d/%y %H:%M";
+    const FORMAT: &str = "%m/%d/%Y";
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
     where
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        let trimmed = s.trim();
-        Ok(NaiveDate::parse_from_str(trimmed, FORMAT)
-            .or_else(|_| NaiveDate::parse_from_str(trimmed, ALT_FORMAT))
-            .or_else(|_| NaiveDate::parse_from_str(trimmed, OTHER_ALT_FORMAT))
-            .ok())
+        Ok(parse_date_or_datetime(
+            &s,
+            MM_DD_YYYY_DATE_FORMATS,
+            MM_DD_YYYY_DATETIME_FORMATS,
+            false,
+        )
+        .ok())
     }
 
-    pub fn serialize<S>(val: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error>
+    // Null tokens are None, but anything else must be a date at midnight
+    pub fn deserialize_strict<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        if is_null_token(&s) {
+            return Ok(None);
+        }
+        parse_date_or_datetime(
+            &s,
+            MM_DD_YYYY_DATE_FORMATS,
+            MM_DD_YYYY_DATETIME_FORMATS,
+            true,
+        )
+        .map(Some)
+        .map_err(serde::de::Error::custom)
+    }
+
+    pub fn serialize<S>(val: &Option<NaiveDate>, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        serializer.serialize_str(&val.format(FORMAT).to_string())
+        match val {
+            Some(val) => serializer.serialize_str(&val.format(FORMAT).to_string()),
+            None => serializer.serialize_none(),
+        }
     }
 }
 
@@ -613,6 +756,7 @@ pub mod nullable_yyyy_mm_dd_datetime {
 }
 
 pub mod hhmm_time {
+    use crate::Scalar;
     use chrono::NaiveTime;
     use serde::{Deserialize, Deserializer, Serializer};
 
@@ -623,7 +767,14 @@ pub mod hhmm_time {
     where
         D: Deserializer<'de>,
     {
-        let s = String::deserialize(deserializer)?;
+        // Numeric input loses leading zeros, e.g. 0730 arrives as 730
+        let s = match Scalar::deserialize(deserializer)? {
+            Scalar::Str(s) => s,
+            x => match x.as_i64() {
+                Some(i) => format!("{:04}", i),
+                None => x.to_string(),
+            },
+        };
         NaiveTime::parse_from_str(&s, FORMAT)
             .or_else(|_| NaiveTime::parse_from_str(&s, ALT_FORMAT))
             .map_err(|e| serde::de::Error::custom(format!("invalid time: {} {:?}", s, e)))
@@ -692,19 +843,37 @@ pub mod va_datetime_opt {
 }
 
 pub mod mssql_date {
-    use chrono::{NaiveDate, NaiveDateTime};
-    use serde::{self, Deserialize, Deserializer};
+    use crate::dates::{parse_date_or_datetime, MSSQL_DATETIME_FORMATS, MSSQL_DATE_FORMATS};
+    use chrono::NaiveDate;
+    use serde::{self, Deserialize, Deserializer, Serializer};
 
-    const FORMAT: &str = "%Y-%m-%d %H:%M:%S.%3f";
+    const FORMAT: &str = "%Y-%m-%d";
 
+    // Accepts "2021-03-14" as well as "2021-03-14 07:30:00.000", dropping the time
     pub fn deserialize<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
     where
         D: Deserializer<'de>,
     {
         let s = String::deserialize(deserializer)?;
-        NaiveDateTime::parse_from_str(&s, FORMAT)
-            .map(|dt| dt.date())
-            .map_err(|e| serde::de::Error::custom(format!("invalid date: {} {:?}", s, e)))
+        parse_date_or_datetime(&s, MSSQL_DATE_FORMATS, MSSQL_DATETIME_FORMATS, false)
+            .map_err(serde::de::Error::custom)
+    }
+
+    // Like `deserialize`, but fails if a time other than midnight is present
+    pub fn deserialize_strict<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        parse_date_or_datetime(&s, MSSQL_DATE_FORMATS, MSSQL_DATETIME_FORMATS, true)
+            .map_err(serde::de::Error::custom)
+    }
+
+    pub fn serialize<S>(val: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&val.format(FORMAT).to_string())
     }
 }
 
@@ -754,15 +923,22 @@ pub mod nullable_mssql_datetime {
 }
 
 pub mod currency {
+    use crate::Scalar;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<f64, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let mut s = String::deserialize(deserializer)?;
-        s = s.trim().replace(&['$', ','] as &[_], "");
-        s.parse::<f64>().map_err(serde::de::Error::custom)
+        match Scalar::deserialize(deserializer)? {
+            Scalar::Str(mut s) => {
+                s = s.trim().replace(&['$', ','] as &[_], "");
+                s.parse::<f64>().map_err(serde::de::Error::custom)
+            }
+            x => x.as_f64().ok_or_else(|| {
+                serde::de::Error::invalid_type(x.unexpected(), &"a currency amount")
+            }),
+        }
     }
 
     pub fn serialize<S>(val: &f64, serializer: S) -> Result<S::Ok, S::Error>
@@ -774,18 +950,22 @@ pub mod currency {
 }
 
 pub mod currency_opt {
+    use crate::Scalar;
     use serde::{self, Deserialize, Deserializer, Serializer};
 
     pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
     where
         D: Deserializer<'de>,
     {
-        let mut s = String::deserialize(deserializer)?;
-        if s.is_empty() {
-            Ok(None)
-        } else {
-            s = s.trim().replace(&['$', ','] as &[_], "");
-            Ok(Some(s.parse::<f64>().map_err(serde::de::Error::custom)?))
+        match Scalar::deserialize(deserializer)? {
+            x if x.is_null() => Ok(None),
+            Scalar::Str(mut s) => {
+                s = s.trim().replace(&['$', ','] as &[_], "");
+                Ok(Some(s.parse::<f64>().map_err(serde::de::Error::custom)?))
+            }
+            x => Ok(Some(x.as_f64().ok_or_else(|| {
+                serde::de::Error::invalid_type(x.unexpected(), &"a currency amount")
+            })?)),
         }
     }
 
@@ -798,7 +978,8 @@ pub mod currency_opt {
 }
 
 pub mod nullable_field {
-    use serde::{self, Deserialize, Deserializer};
+    use crate::{record_warning, scalar::Buffered};
+    use serde::{self, de::IntoDeserializer, Deserialize, Deserializer};
 
     pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
     where
@@ -807,105 +988,57 @@ pub mod nullable_field {
     {
         Ok(T::deserialize(deserializer).ok())
     }
-}
 
-pub mod possibly_empty_parseable_value {
-    use serde::{self, Deserialize, Deserializer};
-    use std::{fmt::Display, str::FromStr};
-
-    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    pub fn deserialize_strict<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
     where
         D: Deserializer<'de>,
-        T: FromStr,
-        T::Err: Display,
+        T: Deserialize<'de>,
     {
-        let s = String::deserialize(deserializer)?;
-        if s.is_empty() {
+        let value = Buffered::deserialize(deserializer)?;
+        if value.is_null() {
             Ok(None)
         } else {
-            Ok(Some(T::from_str(&s).map_err(serde::de::Error::custom)?))
+            T::deserialize(value.into_deserializer()).map(Some)
         }
     }
-}
 
-pub mod enum_from_id {
-    use serde::{de, Deserialize, Deserializer, Serializer};
-    use std::convert::TryFrom;
-
-    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    pub fn deserialize_audited<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
     where
         D: Deserializer<'de>,
-        T: TryFrom<i32>,
+        T: Deserialize<'de>,
     {
-        let id = i32::deserialize(deserializer)?;
-        T::try_from(id).map_err(|_e| {
-            de::Error::invalid_value(de::Unexpected::Unsigned(id as _), &"a valid id")
-        })
-    }
+        let value = Buffered::deserialize(deserializer)?;
+        if value.is_null() {
+            return Ok(None);
+        }
 
-    pub fn serialize<S, T>(val: T, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-        T: Into<i32>,
-    {
-        serializer.serialize_i32(val.into())
+        let text = value.to_string();
+        Ok(T::deserialize(value.into_deserializer())
+            .map_err(|err: D::Error| {
+                record_warning(format!("treating {:?} as null: {}", text, err));
+            })
+            .ok())
     }
 }
 
-pub mod enum_from_id_opt {
-    use serde::{Deserialize, Deserializer, Serializer};
-    use std::convert::TryFrom;
+pub mod possibly_empty_parseable_value {
+    use crate::{is_null_token, Scalar};
+    use serde::{self, Deserialize, Deserializer};
+    use std::{fmt::Display, str::FromStr};
 
     pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
     where
         D: Deserializer<'de>,
-        T: TryFrom<i32>,
-    {
-        Ok(i32::deserialize(deserializer)
-            .ok()
-            .and_then(|id| T::try_from(id).ok()))
-    }
-
-    pub fn serialize<'a, S, T>(val: &'a Option<T>, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-        &'a T: Into<i32>,
+        T: FromStr,
+        T::Err: Display,
     {
-        if let Some(ref val) = val {
-            serializer.serialize_i32(val.into())
+        let s = Scalar::deserialize(deserializer)?.to_string();
+        if is_null_token(&s) {
+            Ok(None)
         } else {
-            serializer.serialize_str("")
+            Ok(Some(T::from_str(&s).map_err(serde::de::Error::custom)?))
         }
     }
-
-    pub fn default<T>() -> Option<T>
-    where
-        T: Default,
-    {
-        Some(T::default())
-    }
-}
-
-pub mod enum_from_id_or_default {
-    use serde::{Deserialize, Deserializer, Serializer};
-    use std::convert::TryFrom;
-
-    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
-    where
-        D: Deserializer<'de>,
-        T: TryFrom<i32> + Default,
-    {
-        let id = i32::deserialize(deserializer)?;
-        Ok(T::try_from(id).unwrap_or_default())
-    }
-
-    pub fn serialize<'a, S, T>(val: &'a T, serializer: S) -> Result<S::Ok, S::Error>
-    where
-        S: Serializer,
-        &'a T: Into<i32>,
-    {
-        serializer.serialize_i32(val.into())
-    }
 }
 
 pub mod line_separated {
@@ -923,6 +1056,7 @@ pub mod line_separated {
         }
     }
 
+    #[allow(clippy::ptr_arg)]
     pub fn serialize<S>(val: &Vec<String>, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
@@ -946,6 +1080,7 @@ pub mod comma_separated {
         }
     }
 
+    #[allow(clippy::ptr_arg)]
     pub fn serialize<S>(val: &Vec<String>, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
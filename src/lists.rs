@@ -0,0 +1,662 @@
+use crate::Scalar;
+use serde::{
+    de::{Error, IntoDeserializer},
+    ser::{self, Impossible},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{
+    collections::{BTreeSet, HashSet},
+    fmt::Display,
+    hash::Hash,
+    str::FromStr,
+};
+
+pub trait ListDelimiter {
+    const DELIMITER: char;
+}
+
+pub struct Comma;
+
+impl ListDelimiter for Comma {
+    const DELIMITER: char = ',';
+}
+
+pub struct Semicolon;
+
+impl ListDelimiter for Semicolon {
+    const DELIMITER: char = ';';
+}
+
+pub struct Pipe;
+
+impl ListDelimiter for Pipe {
+    const DELIMITER: char = '|';
+}
+
+pub struct Tab;
+
+impl ListDelimiter for Tab {
+    const DELIMITER: char = '\t';
+}
+
+// Trimming each element also drops the '\r' of "\r\n" line endings
+pub struct Newline;
+
+impl ListDelimiter for Newline {
+    const DELIMITER: char = '\n';
+}
+
+// Elements are trimmed and empty ones are dropped, so a blank cell is an empty list.
+// An element wrapped in double quotes is kept as written, delimiters included,
+// with `""` standing for a literal quote.
+pub fn split_list(s: &str, delimiter: char) -> Result<Vec<String>, String> {
+    let mut elements = Vec::new();
+    let mut chars = s.chars().peekable();
+
+    loop {
+        while chars
+            .peek()
+            .is_some_and(|&c| c != delimiter && c.is_whitespace())
+        {
+            chars.next();
+        }
+
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            let mut element = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') if chars.peek() == Some(&'"') => {
+                        chars.next();
+                        element.push('"');
+                    }
+                    Some('"') => break,
+                    Some(c) => element.push(c),
+                    None => return Err(format!("unterminated quote in list: {}", s)),
+                }
+            }
+            elements.push(element);
+
+            match chars.find(|&c| c == delimiter || !c.is_whitespace()) {
+                None => break,
+                Some(c) if c == delimiter => {}
+                Some(_) => return Err(format!("unexpected text after quoted element: {}", s)),
+            }
+        } else {
+            let mut element = String::new();
+            let mut ended = true;
+            for c in chars.by_ref() {
+                if c == delimiter {
+                    ended = false;
+                    break;
+                }
+                element.push(c);
+            }
+            let element = element.trim();
+            if !element.is_empty() {
+                elements.push(element.to_string());
+            }
+            if ended {
+                break;
+            }
+        }
+    }
+
+    Ok(elements)
+}
+
+// Quotes only the elements that wouldn't read back as written
+pub fn join_list<I, T>(elements: I, delimiter: char) -> String
+where
+    I: IntoIterator<Item = T>,
+    T: AsRef<str>,
+{
+    let mut joined = String::new();
+    for (i, element) in elements.into_iter().enumerate() {
+        let element = element.as_ref();
+        if i > 0 {
+            joined.push(delimiter);
+        }
+        let needs_quotes = element.is_empty()
+            || element.starts_with(char::is_whitespace)
+            || element.ends_with(char::is_whitespace)
+            || element.contains([delimiter, '"', '\n', '\r']);
+        if needs_quotes {
+            joined.push('"');
+            joined.push_str(&element.replace('"', "\"\""));
+            joined.push('"');
+        } else {
+            joined.push_str(element);
+        }
+    }
+    joined
+}
+
+// Text is split as written; a typed number or bool cell is a single element
+fn split_scalar<E: Error>(scalar: &Scalar, delimiter: char) -> Result<Vec<String>, E> {
+    match scalar {
+        Scalar::Str(s) => split_list(s, delimiter),
+        x => split_list(&x.to_string(), delimiter),
+    }
+    .map_err(E::custom)
+}
+
+fn from_scalar<L, T, E, F>(scalar: Scalar, parse: F) -> Result<Vec<T>, E>
+where
+    L: ListDelimiter,
+    E: Error,
+    F: Fn(String) -> Result<T, E>,
+{
+    split_scalar::<E>(&scalar, L::DELIMITER)?
+        .into_iter()
+        .map(parse)
+        .collect()
+}
+
+fn parse_element<T, E>(element: String) -> Result<T, E>
+where
+    T: FromStr,
+    T::Err: Display,
+    E: Error,
+{
+    element
+        .parse()
+        .map_err(|err| E::custom(format!("invalid list element {:?}: {}", element, err)))
+}
+
+fn deserialize_element<'de, T, E>(element: String) -> Result<T, E>
+where
+    T: Deserialize<'de>,
+    E: Error,
+{
+    T::deserialize(Scalar::Str(element).into_deserializer())
+}
+
+pub mod delimited {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromStr,
+        T::Err: Display,
+    {
+        deserialize_as::<Comma, D, T>(deserializer)
+    }
+
+    // e.g. `#[serde(deserialize_with = "delimited::deserialize_as::<Semicolon, _, _>")]`
+    pub fn deserialize_as<'de, L, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        L: ListDelimiter,
+        D: Deserializer<'de>,
+        T: FromStr,
+        T::Err: Display,
+    {
+        from_scalar::<L, T, D::Error, _>(Scalar::deserialize(deserializer)?, parse_element)
+    }
+
+    pub fn serialize<S, T>(val: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Display,
+    {
+        serialize_as::<Comma, S, T>(val, serializer)
+    }
+
+    pub fn serialize_as<L, S, T>(val: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        L: ListDelimiter,
+        S: Serializer,
+        T: Display,
+    {
+        let elements: Vec<String> = val.iter().map(ToString::to_string).collect();
+        serializer.serialize_str(&join_list(&elements, L::DELIMITER))
+    }
+}
+
+pub mod delimited_opt {
+    use super::*;
+
+    // A blank or null cell is `None` rather than an empty list
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromStr,
+        T::Err: Display,
+    {
+        deserialize_as::<Comma, D, T>(deserializer)
+    }
+
+    pub fn deserialize_as<'de, L, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+    where
+        L: ListDelimiter,
+        D: Deserializer<'de>,
+        T: FromStr,
+        T::Err: Display,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            Ok(None)
+        } else {
+            from_scalar::<L, T, D::Error, _>(scalar, parse_element).map(Some)
+        }
+    }
+
+    pub fn serialize<S, T>(val: &Option<Vec<T>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Display,
+    {
+        serialize_as::<Comma, S, T>(val, serializer)
+    }
+
+    pub fn serialize_as<L, S, T>(val: &Option<Vec<T>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        L: ListDelimiter,
+        S: Serializer,
+        T: Display,
+    {
+        match val {
+            Some(val) => delimited::serialize_as::<L, S, T>(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+// Keeps the first spelling of each element, ignoring case, before elements are parsed
+fn dedup_ignore_case(elements: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    elements
+        .into_iter()
+        .filter(|element| seen.insert(element.to_lowercase()))
+        .collect()
+}
+
+fn from_scalar_ignore_case<L, T, E>(scalar: Scalar) -> Result<Vec<T>, E>
+where
+    L: ListDelimiter,
+    T: FromStr,
+    T::Err: Display,
+    E: Error,
+{
+    let elements = split_scalar::<E>(&scalar, L::DELIMITER)?;
+    dedup_ignore_case(elements)
+        .into_iter()
+        .map(parse_element)
+        .collect()
+}
+
+pub trait SetCollection {
+    type Item;
+
+    fn from_elements(elements: Vec<Self::Item>) -> Self;
+    fn elements(&self) -> Vec<&Self::Item>;
+}
+
+impl<T: Ord> SetCollection for BTreeSet<T> {
+    type Item = T;
+
+    fn from_elements(elements: Vec<T>) -> Self {
+        elements.into_iter().collect()
+    }
+
+    fn elements(&self) -> Vec<&T> {
+        self.iter().collect()
+    }
+}
+
+impl<T: Eq + Hash> SetCollection for HashSet<T> {
+    type Item = T;
+
+    fn from_elements(elements: Vec<T>) -> Self {
+        elements.into_iter().collect()
+    }
+
+    fn elements(&self) -> Vec<&T> {
+        self.iter().collect()
+    }
+}
+
+// Reads a multi-select cell into a `BTreeSet` or `HashSet`
+pub mod delimited_set {
+    use super::*;
+
+    pub fn deserialize<'de, D, C>(deserializer: D) -> Result<C, D::Error>
+    where
+        D: Deserializer<'de>,
+        C: SetCollection,
+        C::Item: FromStr,
+        <C::Item as FromStr>::Err: Display,
+    {
+        deserialize_as::<Comma, D, C>(deserializer)
+    }
+
+    // e.g. `#[serde(deserialize_with = "delimited_set::deserialize_as::<Semicolon, _, _>")]`
+    pub fn deserialize_as<'de, L, D, C>(deserializer: D) -> Result<C, D::Error>
+    where
+        L: ListDelimiter,
+        D: Deserializer<'de>,
+        C: SetCollection,
+        C::Item: FromStr,
+        <C::Item as FromStr>::Err: Display,
+    {
+        delimited::deserialize_as::<L, D, C::Item>(deserializer).map(C::from_elements)
+    }
+
+    // "Smith, SMITH, Jones" reads as {"Smith", "Jones"}
+    pub fn deserialize_ignore_case<'de, D, C>(deserializer: D) -> Result<C, D::Error>
+    where
+        D: Deserializer<'de>,
+        C: SetCollection,
+        C::Item: FromStr,
+        <C::Item as FromStr>::Err: Display,
+    {
+        deserialize_ignore_case_as::<Comma, D, C>(deserializer)
+    }
+
+    pub fn deserialize_ignore_case_as<'de, L, D, C>(deserializer: D) -> Result<C, D::Error>
+    where
+        L: ListDelimiter,
+        D: Deserializer<'de>,
+        C: SetCollection,
+        C::Item: FromStr,
+        <C::Item as FromStr>::Err: Display,
+    {
+        from_scalar_ignore_case::<L, C::Item, D::Error>(Scalar::deserialize(deserializer)?)
+            .map(C::from_elements)
+    }
+
+    pub fn serialize<S, C>(val: &C, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        C: SetCollection,
+        C::Item: Display + Ord,
+    {
+        serialize_as::<Comma, S, C>(val, serializer)
+    }
+
+    // Elements are written in their `Ord` order, so a `HashSet` always writes the same cell
+    // and {2, 10} writes "2,10"
+    pub fn serialize_as<L, S, C>(val: &C, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        L: ListDelimiter,
+        S: Serializer,
+        C: SetCollection,
+        C::Item: Display + Ord,
+    {
+        let mut elements = val.elements();
+        elements.sort();
+        let elements: Vec<String> = elements.iter().map(ToString::to_string).collect();
+        serializer.serialize_str(&join_list(&elements, L::DELIMITER))
+    }
+}
+
+// Reads a list keeping only the first occurrence of each element, in the order written
+pub mod unique_delimited {
+    use super::*;
+
+    fn dedup<T: PartialEq>(elements: Vec<T>) -> Vec<T> {
+        let mut unique: Vec<T> = Vec::with_capacity(elements.len());
+        for element in elements {
+            if !unique.contains(&element) {
+                unique.push(element);
+            }
+        }
+        unique
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromStr + PartialEq,
+        T::Err: Display,
+    {
+        deserialize_as::<Comma, D, T>(deserializer)
+    }
+
+    pub fn deserialize_as<'de, L, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        L: ListDelimiter,
+        D: Deserializer<'de>,
+        T: FromStr + PartialEq,
+        T::Err: Display,
+    {
+        delimited::deserialize_as::<L, D, T>(deserializer).map(dedup)
+    }
+
+    pub fn deserialize_ignore_case<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromStr + PartialEq,
+        T::Err: Display,
+    {
+        deserialize_ignore_case_as::<Comma, D, T>(deserializer)
+    }
+
+    pub fn deserialize_ignore_case_as<'de, L, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        L: ListDelimiter,
+        D: Deserializer<'de>,
+        T: FromStr + PartialEq,
+        T::Err: Display,
+    {
+        from_scalar_ignore_case::<L, T, D::Error>(Scalar::deserialize(deserializer)?).map(dedup)
+    }
+
+    pub fn serialize<S, T>(val: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Display,
+    {
+        delimited::serialize(val, serializer)
+    }
+
+    pub fn serialize_as<L, S, T>(val: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        L: ListDelimiter,
+        S: Serializer,
+        T: Display,
+    {
+        delimited::serialize_as::<L, S, T>(val, serializer)
+    }
+}
+
+// Reads each element as a cell of its own, so a field module can be applied to the
+// elements through a newtype, e.g.
+// `#[derive(Deserialize, Serialize)] struct Visit(#[serde(with = "mm_dd_yyyy_date")] NaiveDate);`
+// with `#[serde(with = "delimited_with")] visits: Vec<Visit>`
+pub mod delimited_with {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        deserialize_as::<Comma, D, T>(deserializer)
+    }
+
+    pub fn deserialize_as<'de, L, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        L: ListDelimiter,
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        from_scalar::<L, T, D::Error, _>(Scalar::deserialize(deserializer)?, deserialize_element)
+    }
+
+    pub fn serialize<S, T>(val: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        serialize_as::<Comma, S, T>(val, serializer)
+    }
+
+    pub fn serialize_as<L, S, T>(val: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        L: ListDelimiter,
+        S: Serializer,
+        T: Serialize,
+    {
+        let elements = val
+            .iter()
+            .map(|element| element.serialize(ElementSerializer))
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(ser::Error::custom)?;
+        serializer.serialize_str(&join_list(&elements, L::DELIMITER))
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ElementError(String);
+
+impl Display for ElementError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ElementError {}
+
+impl ser::Error for ElementError {
+    fn custom<T: Display>(msg: T) -> Self {
+        ElementError(msg.to_string())
+    }
+}
+
+// Writes a single list element as text; only values that fit in one cell are supported
+pub(crate) struct ElementSerializer;
+
+macro_rules! serialize_display {
+    ($($method:ident: $t:ty),*) => {
+        $(
+            fn $method(self, v: $t) -> Result<String, ElementError> {
+                Ok(v.to_string())
+            }
+        )*
+    };
+}
+
+impl Serializer for ElementSerializer {
+    type Ok = String;
+    type Error = ElementError;
+    type SerializeSeq = Impossible<String, ElementError>;
+    type SerializeTuple = Impossible<String, ElementError>;
+    type SerializeTupleStruct = Impossible<String, ElementError>;
+    type SerializeTupleVariant = Impossible<String, ElementError>;
+    type SerializeMap = Impossible<String, ElementError>;
+    type SerializeStruct = Impossible<String, ElementError>;
+    type SerializeStructVariant = Impossible<String, ElementError>;
+
+    serialize_display!(
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+        serialize_str: &str
+    );
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, ElementError> {
+        Err(ser::Error::custom("bytes can't be a list element"))
+    }
+
+    fn serialize_none(self) -> Result<String, ElementError> {
+        Ok(String::new())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<String, ElementError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String, ElementError> {
+        Ok(String::new())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String, ElementError> {
+        Ok(String::new())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String, ElementError> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, ElementError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String, ElementError> {
+        Err(ser::Error::custom("a list element must be a single value"))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, ElementError> {
+        Err(ser::Error::custom("a list element must be a single value"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, ElementError> {
+        Err(ser::Error::custom("a list element must be a single value"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, ElementError> {
+        Err(ser::Error::custom("a list element must be a single value"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, ElementError> {
+        Err(ser::Error::custom("a list element must be a single value"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, ElementError> {
+        Err(ser::Error::custom("a list element must be a single value"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, ElementError> {
+        Err(ser::Error::custom("a list element must be a single value"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, ElementError> {
+        Err(ser::Error::custom("a list element must be a single value"))
+    }
+}
@@ -0,0 +1,397 @@
+use crate::{
+    columns::Tracked,
+    detect::{detect_columns, transpose},
+    with_text_cells, LookupTable, YearPivot,
+};
+use csv::StringRecord;
+use serde::de::DeserializeOwned;
+use std::{cell::RefCell, collections::HashMap, fmt, io::Read, sync::Arc};
+
+thread_local! {
+    static CURRENT: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+}
+
+struct Frame {
+    options: LoadOptions,
+    report: Option<LoadReport>,
+    row: usize,
+    // Per column, from a scan before deserializing; `column` is the one being read
+    headers: Vec<String>,
+    date_formats: Vec<Option<&'static str>>,
+    column: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LoadOptions {
+    pub(crate) null_tokens: Option<Vec<String>>,
+    pub(crate) year_pivot: Option<YearPivot>,
+    pub(crate) detect_date_formats: bool,
+    pub(crate) lookup_tables: HashMap<String, Arc<LookupTable>>,
+}
+
+impl LoadOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn null_tokens<I, S>(mut self, tokens: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        self.null_tokens = Some(
+            tokens
+                .into_iter()
+                .map(|t| t.as_ref().trim().to_string())
+                .collect(),
+        );
+        self
+    }
+
+    // Used by `mm_dd_yy_date::deserialize`; defaults to chrono's `%y` rule
+    pub fn year_pivot(mut self, pivot: YearPivot) -> Self {
+        self.year_pivot = Some(pivot);
+        self
+    }
+
+    // Has the `FromCsv` and `FromXlsx` loaders scan every column before deserializing,
+    // so `any_date` and `any_datetime` prefer the formats the file actually uses
+    pub fn detect_date_formats(mut self) -> Self {
+        self.detect_date_formats = true;
+        self
+    }
+
+    // Registers a table for `lookup` fields whose `LookupSource::TABLE` is `name`
+    pub fn lookup_table<S>(mut self, name: S, table: LookupTable) -> Self
+    where
+        S: Into<String>,
+    {
+        self.lookup_tables.insert(name.into(), Arc::new(table));
+        self
+    }
+
+    // Applies these options to every field deserialized on this thread within `f`,
+    // e.g. `options.run(|| Record::from_csv(path))`
+    pub fn run<F, T>(&self, f: F) -> T
+    where
+        F: FnOnce() -> T,
+    {
+        self.enter(None);
+        let _guard = RunGuard;
+        f()
+    }
+
+    // Like `run`, but collects failed rows and field warnings instead of printing them
+    pub fn run_with_report<F, T>(&self, f: F) -> (T, LoadReport)
+    where
+        F: FnOnce() -> T,
+    {
+        self.enter(Some(LoadReport::default()));
+        let guard = RunGuard;
+        let result = f();
+        let report = guard.finish().unwrap_or_default();
+        (result, report)
+    }
+
+    fn enter(&self, report: Option<LoadReport>) {
+        CURRENT.with(|current| {
+            current.borrow_mut().push(Frame {
+                options: self.clone(),
+                report,
+                row: 0,
+                headers: Vec::new(),
+                date_formats: Vec::new(),
+                column: None,
+            })
+        });
+    }
+}
+
+struct RunGuard;
+
+impl RunGuard {
+    fn finish(self) -> Option<LoadReport> {
+        let report = CURRENT.with(|current| {
+            current
+                .borrow_mut()
+                .last_mut()
+                .and_then(|frame| frame.report.take())
+        });
+        drop(self);
+        report
+    }
+}
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        CURRENT.with(|current| {
+            current.borrow_mut().pop();
+        });
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LoadReport {
+    pub loaded: usize,
+    pub errors: Vec<RowIssue>,
+    pub warnings: Vec<RowIssue>,
+    // Codes read into `CodedValue::Unknown`, in the order first seen
+    pub unknown_codes: Vec<UnknownCode>,
+    // Columns whose dates more than one format reads, to different dates
+    pub ambiguous_date_columns: Vec<AmbiguousDateColumn>,
+}
+
+impl LoadReport {
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty() && self.warnings.is_empty()
+    }
+}
+
+// `row` is the 1-based index of the data row, not counting headers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowIssue {
+    pub row: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RowIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "row {}: {}", self.row, self.message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownCode {
+    pub type_name: &'static str,
+    pub code: String,
+    pub rows: Vec<usize>,
+}
+
+impl fmt::Display for UnknownCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "unknown {} code {:?} in {} row(s)",
+            self.type_name,
+            self.code,
+            self.rows.len()
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AmbiguousDateColumn {
+    pub column: String,
+    // The format the column was read with
+    pub format: &'static str,
+}
+
+impl fmt::Display for AmbiguousDateColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "column {:?} has ambiguous dates, read as {}",
+            self.column, self.format
+        )
+    }
+}
+
+pub(crate) fn with_current<F, T>(f: F) -> T
+where
+    F: FnOnce(Option<&LoadOptions>) -> T,
+{
+    CURRENT.with(|current| f(current.borrow().last().map(|frame| &frame.options)))
+}
+
+pub(crate) fn with_lookup_table<F, T>(name: &str, f: F) -> T
+where
+    F: FnOnce(Option<&LookupTable>) -> T,
+{
+    with_current(|options| {
+        f(options
+            .and_then(|options| options.lookup_tables.get(name))
+            .map(Arc::as_ref))
+    })
+}
+
+pub(crate) fn detection_enabled() -> bool {
+    with_current(|options| options.is_some_and(|options| options.detect_date_formats))
+}
+
+fn with_frame<F>(f: F)
+where
+    F: FnOnce(&mut Frame),
+{
+    CURRENT.with(|current| {
+        if let Some(frame) = current.borrow_mut().last_mut() {
+            f(frame);
+        }
+    });
+}
+
+pub(crate) fn set_detected_date_formats(
+    headers: Vec<String>,
+    date_formats: Vec<Option<&'static str>>,
+) {
+    with_frame(|frame| {
+        frame.headers = headers;
+        frame.date_formats = date_formats;
+        frame.column = None;
+    });
+}
+
+pub(crate) fn enter_column(index: usize) {
+    with_frame(|frame| frame.column = Some(index));
+}
+
+pub(crate) fn enter_column_named(name: &str) {
+    with_frame(|frame| frame.column = frame.headers.iter().position(|header| header == name));
+}
+
+pub(crate) fn detected_date_format() -> Option<&'static str> {
+    CURRENT.with(|current| {
+        current.borrow().last().and_then(|frame| {
+            frame
+                .column
+                .and_then(|column| frame.date_formats.get(column).copied().flatten())
+        })
+    })
+}
+
+fn with_report<F>(f: F) -> bool
+where
+    F: FnOnce(&mut LoadReport, usize),
+{
+    CURRENT.with(|current| match current.borrow_mut().last_mut() {
+        Some(Frame {
+            report: Some(report),
+            row,
+            ..
+        }) => {
+            f(report, *row);
+            true
+        }
+        _ => false,
+    })
+}
+
+pub fn record_warning<S>(message: S)
+where
+    S: Into<String>,
+{
+    let message = message.into();
+    let recorded = with_report(|report, row| {
+        report.warnings.push(RowIssue {
+            row,
+            message: message.clone(),
+        })
+    });
+
+    if !recorded {
+        eprintln!("Warning deserializing record: {}", message);
+    }
+}
+
+pub fn record_unknown_code<S>(type_name: &'static str, code: S)
+where
+    S: Into<String>,
+{
+    let code = code.into();
+    with_report(|report, row| {
+        match report
+            .unknown_codes
+            .iter_mut()
+            .find(|unknown| unknown.type_name == type_name && unknown.code == code)
+        {
+            Some(unknown) => unknown.rows.push(row),
+            None => report.unknown_codes.push(UnknownCode {
+                type_name,
+                code,
+                rows: vec![row],
+            }),
+        }
+    });
+}
+
+pub(crate) fn record_ambiguous_dates(column: String, format: &'static str) {
+    let ambiguous = AmbiguousDateColumn { column, format };
+    let message = ambiguous.to_string();
+    let recorded = with_report(|report, _| report.ambiguous_date_columns.push(ambiguous));
+
+    if !recorded {
+        eprintln!("Warning detecting date formats: {}", message);
+    }
+}
+
+// Row numbers restart with each load, even when several share one report
+fn reset_row() {
+    with_frame(|frame| frame.row = 0);
+}
+
+fn start_row() {
+    with_frame(|frame| frame.row += 1);
+}
+
+// Like `load_rows` over `reader.deserialize()`, with cells read as their text. Buffers
+// the records to scan their columns first when date format detection is enabled.
+pub(crate) fn load_csv<T, R>(mut reader: csv::Reader<R>) -> Result<Vec<T>, csv::Error>
+where
+    T: DeserializeOwned,
+    R: Read,
+{
+    if !detection_enabled() {
+        let rows = reader.deserialize().map(|row| row.map(Tracked::into_inner));
+        return Ok(with_text_cells(|| load_rows(rows)));
+    }
+
+    let headers = reader.headers()?.clone();
+    let records: Vec<Result<StringRecord, csv::Error>> = reader.records().collect();
+    detect_columns(
+        headers.iter().map(String::from).collect(),
+        transpose(
+            records
+                .iter()
+                .filter_map(|record| record.as_ref().ok())
+                .map(|record| record.iter()),
+        ),
+    );
+
+    let rows = records.into_iter().map(|record| {
+        record.and_then(|record| record.deserialize(Some(&headers)).map(Tracked::into_inner))
+    });
+    Ok(with_text_cells(|| load_rows(rows)))
+}
+
+pub(crate) fn load_rows<T, E, I>(mut rows: I) -> Vec<T>
+where
+    I: Iterator<Item = Result<T, E>>,
+    E: fmt::Debug + fmt::Display,
+{
+    let mut records = Vec::new();
+    reset_row();
+
+    loop {
+        start_row();
+        match rows.next() {
+            Some(Ok(record)) => {
+                with_report(|report, _| report.loaded += 1);
+                records.push(record);
+            }
+            Some(Err(err)) => {
+                let recorded = with_report(|report, row| {
+                    report.errors.push(RowIssue {
+                        row,
+                        message: err.to_string(),
+                    })
+                });
+
+                if !recorded {
+                    eprintln!("Failed deserializing record: {:?}", &err);
+                }
+            }
+            None => break,
+        }
+    }
+
+    records
+}
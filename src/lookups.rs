@@ -0,0 +1,236 @@
+use crate::{codes::raw_code, load::with_lookup_table, record_warning, FromCsv, Scalar};
+use serde::{de::DeserializeOwned, de::Error, Deserialize, Deserializer, Serializer};
+use std::{
+    collections::HashMap, fmt::Display, io::Read, iter::FromIterator, path::Path, str::FromStr,
+};
+
+#[cfg(feature = "calamine")]
+use crate::FromXlsx;
+
+// A row of a mapping file, e.g. a provider id and the provider's name
+pub trait LookupEntry {
+    fn code(&self) -> &str;
+    fn value(&self) -> &str;
+}
+
+// Codes are trimmed, and compared ignoring case if `ignore_case` is set.
+// A code listed twice maps to its last value.
+#[derive(Debug, Clone, Default)]
+pub struct LookupTable {
+    values: HashMap<String, String>,
+    ignore_case: bool,
+}
+
+impl LookupTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_pairs<I, K, V>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<str>,
+        V: Into<String>,
+    {
+        let mut table = Self::new();
+        for (code, value) in pairs {
+            table.insert(code.as_ref(), value);
+        }
+        table
+    }
+
+    // e.g. `LookupTable::from_csv::<ProviderRow, _>("providers.csv")?`
+    pub fn from_csv<T, P>(path: P) -> Result<Self, csv::Error>
+    where
+        T: FromCsv + DeserializeOwned + LookupEntry,
+        P: AsRef<Path>,
+    {
+        T::from_csv(path).map(Self::from_iter)
+    }
+
+    pub fn from_csv_reader<T, R>(reader: R) -> Result<Self, csv::Error>
+    where
+        T: FromCsv + DeserializeOwned + LookupEntry,
+        R: Read,
+    {
+        T::from_csv_reader(reader).map(Self::from_iter)
+    }
+
+    #[cfg(feature = "calamine")]
+    pub fn from_xlsx_path<T, P>(path: P) -> Result<Self, calamine::Error>
+    where
+        T: FromXlsx + DeserializeOwned + LookupEntry,
+        P: AsRef<Path>,
+    {
+        T::from_xlsx_path(path).map(Self::from_iter)
+    }
+
+    // Set before inserting, since codes are stored in the form they're matched in
+    pub fn ignore_case(mut self) -> Self {
+        self.ignore_case = true;
+        self.values = self
+            .values
+            .into_iter()
+            .map(|(code, value)| (code.to_lowercase(), value))
+            .collect();
+        self
+    }
+
+    pub fn insert<V>(&mut self, code: &str, value: V)
+    where
+        V: Into<String>,
+    {
+        let key = self.key(code);
+        self.values.insert(key, value.into());
+    }
+
+    pub fn get(&self, code: &str) -> Option<&str> {
+        self.values.get(&self.key(code)).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    fn key(&self, code: &str) -> String {
+        if self.ignore_case {
+            code.trim().to_lowercase()
+        } else {
+            code.trim().to_string()
+        }
+    }
+}
+
+impl<T: LookupEntry> FromIterator<T> for LookupTable {
+    fn from_iter<I: IntoIterator<Item = T>>(entries: I) -> Self {
+        let mut table = Self::new();
+        for entry in entries {
+            table.insert(entry.code(), entry.value());
+        }
+        table
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LookupMiss {
+    Error,
+    // Keeps the code itself as the value
+    PassThrough,
+    // Like `PassThrough`, but records a warning for the row
+    Warn,
+    // Reads as `None` in `lookup_opt`; an error in `lookup`
+    Null,
+}
+
+// Names the table a field is resolved through, as registered with
+// `LoadOptions::lookup_table`, e.g.
+// impl LookupSource for Providers { const TABLE: &'static str = "providers"; }
+// with `#[serde(deserialize_with = "lookup::deserialize_as::<Providers, _, _>")]`
+pub trait LookupSource {
+    const TABLE: &'static str;
+    const ON_MISS: LookupMiss = LookupMiss::Error;
+}
+
+// `Ok(None)` is a miss that reads as null
+fn resolve<L, T, E>(code: String) -> Result<Option<T>, E>
+where
+    L: LookupSource,
+    T: FromStr,
+    T::Err: Display,
+    E: Error,
+{
+    let found = with_lookup_table(L::TABLE, |table| {
+        table.map(|table| table.get(&code).map(str::to_string))
+    })
+    .ok_or_else(|| E::custom(format!("lookup table {:?} is not loaded", L::TABLE)))?;
+
+    let value = match (found, L::ON_MISS) {
+        (Some(value), _) => value,
+        (None, LookupMiss::PassThrough) => code,
+        (None, LookupMiss::Warn) => {
+            record_warning(format!(
+                "{:?} not found in lookup table {:?}",
+                code,
+                L::TABLE
+            ));
+            code
+        }
+        (None, LookupMiss::Null) => return Ok(None),
+        (None, LookupMiss::Error) => {
+            return Err(E::custom(format!(
+                "{:?} not found in lookup table {:?}",
+                code,
+                L::TABLE
+            )))
+        }
+    };
+
+    value
+        .parse()
+        .map(Some)
+        .map_err(|err| E::custom(format!("invalid value {:?}: {}", value, err)))
+}
+
+pub mod lookup {
+    use super::*;
+
+    pub fn deserialize_as<'de, L, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        L: LookupSource,
+        D: Deserializer<'de>,
+        T: FromStr,
+        T::Err: Display,
+    {
+        let code = raw_code(Scalar::deserialize(deserializer)?);
+        resolve::<L, T, D::Error>(code.clone())?.ok_or_else(|| {
+            D::Error::custom(format!(
+                "{:?} not found in lookup table {:?}",
+                code,
+                L::TABLE
+            ))
+        })
+    }
+
+    // Writes the resolved value, not the original code
+    pub fn serialize<S, T>(val: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Display,
+    {
+        serializer.collect_str(val)
+    }
+}
+
+pub mod lookup_opt {
+    use super::*;
+
+    pub fn deserialize_as<'de, L, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        L: LookupSource,
+        D: Deserializer<'de>,
+        T: FromStr,
+        T::Err: Display,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            Ok(None)
+        } else {
+            resolve::<L, T, D::Error>(raw_code(scalar))
+        }
+    }
+
+    pub fn serialize<S, T>(val: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Display,
+    {
+        match val {
+            Some(val) => serializer.collect_str(val),
+            None => serializer.serialize_none(),
+        }
+    }
+}
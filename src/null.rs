@@ -0,0 +1,49 @@
+use crate::load::with_current;
+
+use std::sync::RwLock;
+
+const DEFAULT_NULL_TOKENS: &[&str] = &["", "NULL", "NA"];
+
+static NULL_TOKENS: RwLock<Option<Vec<String>>> = RwLock::new(None);
+
+// Tokens are compared against trimmed values, so whitespace-only values match ""
+pub fn set_null_tokens<I, S>(tokens: I)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let tokens = tokens
+        .into_iter()
+        .map(|t| t.as_ref().trim().to_string())
+        .collect();
+    *NULL_TOKENS.write().unwrap_or_else(|e| e.into_inner()) = Some(tokens);
+}
+
+pub fn reset_null_tokens() {
+    *NULL_TOKENS.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+pub fn null_tokens() -> Vec<String> {
+    with_current(
+        |options| match options.and_then(|o| o.null_tokens.as_ref()) {
+            Some(tokens) => tokens.clone(),
+            None => match &*NULL_TOKENS.read().unwrap_or_else(|e| e.into_inner()) {
+                Some(tokens) => tokens.clone(),
+                None => DEFAULT_NULL_TOKENS.iter().map(|t| t.to_string()).collect(),
+            },
+        },
+    )
+}
+
+pub fn is_null_token(s: &str) -> bool {
+    let s = s.trim();
+    with_current(
+        |options| match options.and_then(|o| o.null_tokens.as_ref()) {
+            Some(tokens) => tokens.iter().any(|t| t == s),
+            None => match &*NULL_TOKENS.read().unwrap_or_else(|e| e.into_inner()) {
+                Some(tokens) => tokens.iter().any(|t| t == s),
+                None => DEFAULT_NULL_TOKENS.contains(&s),
+            },
+        },
+    )
+}
@@ -0,0 +1,268 @@
+use crate::Scalar;
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat {
+    pub decimal_mark: char,
+    pub grouping_separator: Option<char>,
+}
+
+impl NumberFormat {
+    pub const US: NumberFormat = NumberFormat {
+        decimal_mark: '.',
+        grouping_separator: Some(','),
+    };
+
+    pub const EUROPEAN: NumberFormat = NumberFormat {
+        decimal_mark: ',',
+        grouping_separator: Some('.'),
+    };
+
+    pub const SPACED: NumberFormat = NumberFormat {
+        decimal_mark: ',',
+        grouping_separator: Some(' '),
+    };
+
+    pub const PLAIN: NumberFormat = NumberFormat {
+        decimal_mark: '.',
+        grouping_separator: None,
+    };
+
+    // Whitespace (including non-breaking spaces) is always treated as grouping,
+    // and a trailing percent sign divides by 100, so "12,5 %" is 0.125 in EUROPEAN
+    pub fn parse<T: Number>(&self, s: &str) -> Option<T> {
+        let s = s.trim();
+        let (s, percent) = match s.strip_suffix('%') {
+            Some(stripped) => (stripped.trim_end(), true),
+            None => (s, false),
+        };
+
+        let canonical: String = s
+            .chars()
+            .filter(|c| !c.is_whitespace() && Some(*c) != self.grouping_separator)
+            .map(|c| if c == self.decimal_mark { '.' } else { c })
+            .collect();
+
+        if percent {
+            canonical
+                .parse::<f64>()
+                .ok()
+                .and_then(|f| T::from_f64(f / 100.0))
+        } else {
+            T::from_canonical(&canonical)
+        }
+    }
+
+    pub fn format<T: Number>(&self, val: &T) -> String {
+        let canonical = val.to_canonical();
+        let (sign, unsigned) = match canonical.strip_prefix('-') {
+            Some(unsigned) => ("-", unsigned),
+            None => ("", canonical.as_str()),
+        };
+        let (whole, fraction) = match unsigned.find('.') {
+            Some(i) => (&unsigned[..i], Some(&unsigned[i + 1..])),
+            None => (unsigned, None),
+        };
+
+        let mut formatted = String::from(sign);
+        for (i, c) in whole.chars().enumerate() {
+            if let Some(separator) = self.grouping_separator {
+                if i > 0 && (whole.len() - i) % 3 == 0 {
+                    formatted.push(separator);
+                }
+            }
+            formatted.push(c);
+        }
+        if let Some(fraction) = fraction {
+            formatted.push(self.decimal_mark);
+            formatted.push_str(fraction);
+        }
+        formatted
+    }
+}
+
+pub trait NumberLocale {
+    const FORMAT: NumberFormat;
+}
+
+pub struct UsLocale;
+
+impl NumberLocale for UsLocale {
+    const FORMAT: NumberFormat = NumberFormat::US;
+}
+
+pub struct EuropeanLocale;
+
+impl NumberLocale for EuropeanLocale {
+    const FORMAT: NumberFormat = NumberFormat::EUROPEAN;
+}
+
+pub struct SpacedLocale;
+
+impl NumberLocale for SpacedLocale {
+    const FORMAT: NumberFormat = NumberFormat::SPACED;
+}
+
+pub struct PlainLocale;
+
+impl NumberLocale for PlainLocale {
+    const FORMAT: NumberFormat = NumberFormat::PLAIN;
+}
+
+pub trait Number: Sized {
+    // `s` uses '.' as the decimal mark, without grouping, and may use scientific notation
+    fn from_canonical(s: &str) -> Option<Self>;
+    fn from_f64(f: f64) -> Option<Self>;
+    fn to_canonical(&self) -> String;
+}
+
+macro_rules! impl_int_number {
+    ($($t:ty),*) => {
+        $(
+            impl Number for $t {
+                fn from_canonical(s: &str) -> Option<Self> {
+                    s.parse::<$t>()
+                        .ok()
+                        .or_else(|| s.parse::<f64>().ok().and_then(Self::from_f64))
+                }
+
+                fn from_f64(f: f64) -> Option<Self> {
+                    if f.fract() == 0.0 && f >= <$t>::MIN as f64 && f <= <$t>::MAX as f64 {
+                        Some(f as $t)
+                    } else {
+                        None
+                    }
+                }
+
+                fn to_canonical(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_float_number {
+    ($($t:ty),*) => {
+        $(
+            impl Number for $t {
+                fn from_canonical(s: &str) -> Option<Self> {
+                    s.parse::<$t>().ok()
+                }
+
+                fn from_f64(f: f64) -> Option<Self> {
+                    Some(f as $t)
+                }
+
+                fn to_canonical(&self) -> String {
+                    self.to_string()
+                }
+            }
+        )*
+    };
+}
+
+impl_int_number!(i8, i16, i32, i64, u8, u16, u32, u64, usize, isize);
+impl_float_number!(f32, f64);
+
+fn from_scalar<L, T, E>(scalar: Scalar) -> Result<T, E>
+where
+    L: NumberLocale,
+    T: Number,
+    E: Error,
+{
+    // Only text is in the locale's format; typed cells already hold the number
+    let parsed = match &scalar {
+        Scalar::Str(s) => L::FORMAT.parse(s),
+        Scalar::Int(_) | Scalar::UInt(_) | Scalar::Float(_) => {
+            T::from_canonical(&scalar.to_string())
+        }
+        x => return Err(E::invalid_type(x.unexpected(), &"a number")),
+    };
+    parsed.ok_or_else(|| E::custom(format!("invalid number: {}", scalar)))
+}
+
+pub mod number {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Number,
+    {
+        deserialize_as::<UsLocale, D, T>(deserializer)
+    }
+
+    // e.g. `#[serde(deserialize_with = "number::deserialize_as::<EuropeanLocale, _, _>")]`
+    pub fn deserialize_as<'de, L, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        L: NumberLocale,
+        D: Deserializer<'de>,
+        T: Number,
+    {
+        from_scalar::<L, T, D::Error>(Scalar::deserialize(deserializer)?)
+    }
+
+    pub fn serialize<S, T>(val: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Number,
+    {
+        serialize_as::<PlainLocale, S, T>(val, serializer)
+    }
+
+    pub fn serialize_as<L, S, T>(val: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        L: NumberLocale,
+        S: Serializer,
+        T: Number,
+    {
+        serializer.serialize_str(&L::FORMAT.format(val))
+    }
+}
+
+pub mod number_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Number,
+    {
+        deserialize_as::<UsLocale, D, T>(deserializer)
+    }
+
+    pub fn deserialize_as<'de, L, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        L: NumberLocale,
+        D: Deserializer<'de>,
+        T: Number,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            Ok(None)
+        } else {
+            from_scalar::<L, T, D::Error>(scalar).map(Some)
+        }
+    }
+
+    pub fn serialize<S, T>(val: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Number,
+    {
+        serialize_as::<PlainLocale, S, T>(val, serializer)
+    }
+
+    pub fn serialize_as<L, S, T>(val: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        L: NumberLocale,
+        S: Serializer,
+        T: Number,
+    {
+        match val {
+            Some(val) => serializer.serialize_str(&L::FORMAT.format(val)),
+            None => serializer.serialize_none(),
+        }
+    }
+}
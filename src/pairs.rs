@@ -0,0 +1,363 @@
+use crate::{
+    join_list,
+    lists::{ElementError, ElementSerializer},
+    split_list, ListDelimiter, Scalar, Semicolon,
+};
+use serde::{
+    de::{value::MapDeserializer, Error},
+    ser::{self, Impossible, SerializeMap, SerializeStruct},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+// Splits "ASA=3;Emergency=Y" into trimmed pairs; a pair is split at its first '=',
+// and a pair quoted as a whole may contain the delimiter
+pub fn split_pairs(s: &str, delimiter: char) -> Result<Vec<(String, String)>, String> {
+    split_list(s, delimiter)?
+        .into_iter()
+        .map(|pair| match pair.find('=') {
+            Some(i) => Ok((
+                pair[..i].trim().to_string(),
+                pair[i + 1..].trim().to_string(),
+            )),
+            None => Err(format!("expected key=value: {}", pair)),
+        })
+        .collect()
+}
+
+pub fn join_pairs<I, K, V>(pairs: I, delimiter: char) -> String
+where
+    I: IntoIterator<Item = (K, V)>,
+    K: AsRef<str>,
+    V: AsRef<str>,
+{
+    let pairs: Vec<String> = pairs
+        .into_iter()
+        .map(|(key, value)| format!("{}={}", key.as_ref(), value.as_ref()))
+        .collect();
+    join_list(&pairs, delimiter)
+}
+
+fn from_scalar<'de, L, T, E>(scalar: Scalar) -> Result<T, E>
+where
+    L: ListDelimiter,
+    T: Deserialize<'de>,
+    E: Error,
+{
+    let pairs = split_pairs(&scalar.to_string(), L::DELIMITER).map_err(E::custom)?;
+    T::deserialize(MapDeserializer::<_, E>::new(
+        pairs
+            .into_iter()
+            .map(|(key, value)| (key, Scalar::Str(value))),
+    ))
+}
+
+fn to_pairs<L, T, S>(val: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    L: ListDelimiter,
+    T: Serialize,
+    S: Serializer,
+{
+    let pairs = val
+        .serialize(PairsSerializer::default())
+        .map_err(ser::Error::custom)?;
+    serializer.serialize_str(&join_pairs(pairs, L::DELIMITER))
+}
+
+// Reads into a `HashMap<String, String>`, or into a struct whose fields are read
+// like cells, so they can use the crate's field modules too
+pub mod key_value {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        deserialize_as::<Semicolon, D, T>(deserializer)
+    }
+
+    // e.g. `#[serde(deserialize_with = "key_value::deserialize_as::<Pipe, _, _>")]`
+    pub fn deserialize_as<'de, L, D, T>(deserializer: D) -> Result<T, D::Error>
+    where
+        L: ListDelimiter,
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        from_scalar::<L, T, D::Error>(Scalar::deserialize(deserializer)?)
+    }
+
+    pub fn serialize<S, T>(val: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        serialize_as::<Semicolon, S, T>(val, serializer)
+    }
+
+    pub fn serialize_as<L, S, T>(val: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        L: ListDelimiter,
+        S: Serializer,
+        T: Serialize,
+    {
+        to_pairs::<L, T, S>(val, serializer)
+    }
+}
+
+pub mod key_value_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        deserialize_as::<Semicolon, D, T>(deserializer)
+    }
+
+    pub fn deserialize_as<'de, L, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        L: ListDelimiter,
+        D: Deserializer<'de>,
+        T: Deserialize<'de>,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            Ok(None)
+        } else {
+            from_scalar::<L, T, D::Error>(scalar).map(Some)
+        }
+    }
+
+    pub fn serialize<S, T>(val: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Serialize,
+    {
+        serialize_as::<Semicolon, S, T>(val, serializer)
+    }
+
+    pub fn serialize_as<L, S, T>(val: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        L: ListDelimiter,
+        S: Serializer,
+        T: Serialize,
+    {
+        match val {
+            Some(val) => to_pairs::<L, T, S>(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+// Collects a map or struct into pairs, writing each value as a single cell
+#[derive(Default)]
+struct PairsSerializer {
+    pairs: Vec<(String, String)>,
+    key: Option<String>,
+}
+
+fn not_pairs<T>() -> Result<T, ElementError> {
+    Err(ser::Error::custom("key=value pairs need a map or struct"))
+}
+
+impl Serializer for PairsSerializer {
+    type Ok = Vec<(String, String)>;
+    type Error = ElementError;
+    type SerializeSeq = Impossible<Self::Ok, ElementError>;
+    type SerializeTuple = Impossible<Self::Ok, ElementError>;
+    type SerializeTupleStruct = Impossible<Self::Ok, ElementError>;
+    type SerializeTupleVariant = Impossible<Self::Ok, ElementError>;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Impossible<Self::Ok, ElementError>;
+
+    fn serialize_bool(self, _v: bool) -> Result<Self::Ok, ElementError> {
+        not_pairs()
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<Self::Ok, ElementError> {
+        not_pairs()
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<Self::Ok, ElementError> {
+        not_pairs()
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<Self::Ok, ElementError> {
+        not_pairs()
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Self::Ok, ElementError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Self::Ok, ElementError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Self::Ok, ElementError> {
+        self.serialize_i64(v.into())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Self::Ok, ElementError> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Self::Ok, ElementError> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Self::Ok, ElementError> {
+        self.serialize_u64(v.into())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Self::Ok, ElementError> {
+        self.serialize_f64(v.into())
+    }
+
+    fn serialize_char(self, _v: char) -> Result<Self::Ok, ElementError> {
+        not_pairs()
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<Self::Ok, ElementError> {
+        not_pairs()
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, ElementError> {
+        not_pairs()
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, ElementError> {
+        not_pairs()
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, ElementError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, ElementError> {
+        not_pairs()
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, ElementError> {
+        not_pairs()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<Self::Ok, ElementError> {
+        not_pairs()
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, ElementError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, ElementError> {
+        not_pairs()
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, ElementError> {
+        not_pairs()
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, ElementError> {
+        not_pairs()
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, ElementError> {
+        not_pairs()
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, ElementError> {
+        not_pairs()
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, ElementError> {
+        Ok(self)
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, ElementError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, ElementError> {
+        not_pairs()
+    }
+}
+
+impl SerializeMap for PairsSerializer {
+    type Ok = Vec<(String, String)>;
+    type Error = ElementError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), ElementError> {
+        self.key = Some(key.serialize(ElementSerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), ElementError> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| ser::Error::custom("value without a key"))?;
+        self.pairs.push((key, value.serialize(ElementSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, ElementError> {
+        Ok(self.pairs)
+    }
+}
+
+impl SerializeStruct for PairsSerializer {
+    type Ok = Vec<(String, String)>;
+    type Error = ElementError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ElementError> {
+        self.pairs
+            .push((key.to_string(), value.serialize(ElementSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, ElementError> {
+        Ok(self.pairs)
+    }
+}
@@ -0,0 +1,342 @@
+use crate::{dates::NUM_DAYS_1900_01_01_FROM_CE, is_null_token, Scalar};
+use chrono::{Datelike, NaiveDate};
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+use std::{convert::TryFrom, fmt, ops::RangeInclusive, str::FromStr};
+
+// Placeholders for an unknown month or day, e.g. "UNK/2015" or "03/00/2015"
+const UNKNOWN_COMPONENTS: &[&str] = &["UN", "UNK", "UK", "XX", "??", "--", "00", "0"];
+// Values meaning the whole date is unknown, read as None by `partial_date_opt`
+const UNKNOWN_DATES: &[&str] = &["UNKNOWN", "UNK", "UK", "?"];
+const MONTH_NAMES: &[&str] = &[
+    "JANUARY",
+    "FEBRUARY",
+    "MARCH",
+    "APRIL",
+    "MAY",
+    "JUNE",
+    "JULY",
+    "AUGUST",
+    "SEPTEMBER",
+    "OCTOBER",
+    "NOVEMBER",
+    "DECEMBER",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum DatePrecision {
+    Year,
+    Month,
+    Day,
+}
+
+// A date that may be known only to year or year-month precision; `day` is never set without `month`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PartialDate {
+    year: i32,
+    month: Option<u32>,
+    day: Option<u32>,
+}
+
+impl PartialDate {
+    pub fn new(year: i32, month: Option<u32>, day: Option<u32>) -> Option<Self> {
+        match (month, day) {
+            (None, Some(_)) => return None,
+            (Some(month), None) if !(1..=12).contains(&month) => return None,
+            (Some(month), Some(day)) => {
+                NaiveDate::from_ymd_opt(year, month, day)?;
+            }
+            _ => {}
+        }
+        let date = PartialDate { year, month, day };
+        date.earliest_opt()?;
+        date.latest_opt()?;
+        Some(date)
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> Option<u32> {
+        self.month
+    }
+
+    pub fn day(&self) -> Option<u32> {
+        self.day
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.day.is_some()
+    }
+
+    pub fn precision(&self) -> DatePrecision {
+        match (self.month, self.day) {
+            (_, Some(_)) => DatePrecision::Day,
+            (Some(_), None) => DatePrecision::Month,
+            (None, None) => DatePrecision::Year,
+        }
+    }
+
+    pub fn earliest(&self) -> NaiveDate {
+        self.earliest_opt().expect("validated on construction")
+    }
+
+    pub fn latest(&self) -> NaiveDate {
+        self.latest_opt().expect("validated on construction")
+    }
+
+    fn earliest_opt(&self) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(self.year, self.month.unwrap_or(1), self.day.unwrap_or(1))
+    }
+
+    fn latest_opt(&self) -> Option<NaiveDate> {
+        match (self.month, self.day) {
+            (Some(month), Some(day)) => NaiveDate::from_ymd_opt(self.year, month, day),
+            (Some(12), None) | (None, _) => NaiveDate::from_ymd_opt(self.year, 12, 31),
+            (Some(month), None) => {
+                NaiveDate::from_ymd_opt(self.year, month + 1, 1).and_then(|first| first.pred_opt())
+            }
+        }
+    }
+
+    pub fn range(&self) -> RangeInclusive<NaiveDate> {
+        self.earliest()..=self.latest()
+    }
+
+    pub fn contains(&self, date: &NaiveDate) -> bool {
+        self.range().contains(date)
+    }
+
+    // True only when every date this could be falls before `date`
+    pub fn is_before(&self, date: &NaiveDate) -> bool {
+        self.latest() < *date
+    }
+
+    // True only when every date this could be falls after `date`
+    pub fn is_after(&self, date: &NaiveDate) -> bool {
+        self.earliest() > *date
+    }
+
+    pub fn overlaps(&self, other: &PartialDate) -> bool {
+        self.earliest() <= other.latest() && other.earliest() <= self.latest()
+    }
+
+    pub fn from_excel_serial(serial: f64) -> Option<Self> {
+        let days = i32::try_from(serial.trunc() as i64).ok()?;
+        NaiveDate::from_num_days_from_ce_opt(days.checked_add(NUM_DAYS_1900_01_01_FROM_CE)?)
+            .map(PartialDate::from)
+    }
+
+    // Accepts ISO forms as well as "03/2015", "3/14/2015", "UNK/2015", "Mar 2015" and "March 2015"
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if let Some(date) = PartialDate::parse_iso8601(s) {
+            return Some(date);
+        }
+
+        let parts: Vec<&str> = s.split(['/', '-']).map(str::trim).collect();
+        match parts.as_slice() {
+            [month, year] => PartialDate::from_components(year, Some(month), None),
+            [month, day, year] => PartialDate::from_components(year, Some(month), Some(day)),
+            _ => {
+                let (month, year) = s.split_once(char::is_whitespace)?;
+                // A full month name or its three-letter abbreviation, e.g. "March" or "Mar."
+                let month = month.trim_end_matches('.').to_uppercase();
+                let month = MONTH_NAMES.iter().position(|name| {
+                    *name == month || (month.len() == 3 && name.starts_with(&month))
+                })?;
+                PartialDate::new(parse_year(year.trim())?, Some(month as u32 + 1), None)
+            }
+        }
+    }
+
+    fn from_components(year: &str, month: Option<&str>, day: Option<&str>) -> Option<Self> {
+        let year = parse_year(year)?;
+        let month = month.and_then(parse_component).transpose().ok()?;
+        let day = match month {
+            Some(_) => day.and_then(parse_component).transpose().ok()?,
+            None => None,
+        };
+        PartialDate::new(year, month, day)
+    }
+
+    pub fn to_date(&self) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt(self.year, self.month?, self.day?)
+    }
+
+    // Accepts "2015", "2015-03" and "2015-03-14"
+    pub fn parse_iso8601(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let mut parts = s.split('-');
+        let year = parts.next().filter(|y| y.len() == 4)?.parse().ok()?;
+        let mut component = || -> Option<Option<u32>> {
+            match parts.next() {
+                Some(p) if p.len() == 2 => p.parse().ok().map(Some),
+                Some(_) => None,
+                None => Some(None),
+            }
+        };
+        let month = component()?;
+        let day = component()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        PartialDate::new(year, month, day)
+    }
+}
+
+fn parse_year(s: &str) -> Option<i32> {
+    if s.len() == 4 && s.chars().all(|c| c.is_ascii_digit()) {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
+
+// None for an unknown placeholder, Some(Err) when it is neither a number nor a placeholder
+fn parse_component(s: &str) -> Option<Result<u32, ()>> {
+    if UNKNOWN_COMPONENTS.iter().any(|u| u.eq_ignore_ascii_case(s)) {
+        None
+    } else if (1..=2).contains(&s.len()) {
+        Some(s.parse().map_err(|_| ()))
+    } else {
+        Some(Err(()))
+    }
+}
+
+impl From<NaiveDate> for PartialDate {
+    fn from(date: NaiveDate) -> Self {
+        PartialDate {
+            year: date.year(),
+            month: Some(date.month()),
+            day: Some(date.day()),
+        }
+    }
+}
+
+impl fmt::Display for PartialDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}", self.year)?;
+        if let Some(month) = self.month {
+            write!(f, "-{:02}", month)?;
+        }
+        if let Some(day) = self.day {
+            write!(f, "-{:02}", day)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for PartialDate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PartialDate::parse(s).ok_or_else(|| format!("invalid partial date: {}", s))
+    }
+}
+
+// Typed integers are a year, as is text like "2015". xlsx stores every number as a float,
+// so a whole float in 1000..=9999 is also a year; other floats are Excel date serials.
+fn from_scalar<E: Error>(scalar: Scalar) -> Result<PartialDate, E> {
+    let parsed = match &scalar {
+        Scalar::Str(s) => PartialDate::parse(s),
+        Scalar::Int(_) | Scalar::UInt(_) => scalar
+            .as_i64()
+            .and_then(|year| i32::try_from(year).ok())
+            .filter(|year| (1000..=9999).contains(year))
+            .and_then(|year| PartialDate::new(year, None, None)),
+        Scalar::Float(year) if year.fract() == 0.0 && (1000.0..=9999.0).contains(year) => {
+            PartialDate::new(*year as i32, None, None)
+        }
+        Scalar::Float(serial) => PartialDate::from_excel_serial(*serial),
+        x => return Err(E::invalid_type(x.unexpected(), &"a partial date")),
+    };
+    parsed.ok_or_else(|| E::custom(format!("invalid partial date: {}", scalar)))
+}
+
+fn format_us(val: &PartialDate) -> String {
+    match (val.month, val.day) {
+        (Some(month), Some(day)) => format!("{:02}/{:02}/{:04}", month, day, val.year),
+        (Some(month), None) => format!("{:02}/{:04}", month, val.year),
+        _ => format!("{:04}", val.year),
+    }
+}
+
+pub mod partial_date {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<PartialDate, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        from_scalar(Scalar::deserialize(deserializer)?)
+    }
+
+    pub fn serialize<S>(val: &PartialDate, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&val.to_string())
+    }
+
+    // Writes "03/14/2015", "03/2015" or "2015"
+    pub mod us {
+        pub use super::deserialize;
+        use super::{format_us, PartialDate, Serializer};
+
+        pub fn serialize<S>(val: &PartialDate, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            serializer.serialize_str(&format_us(val))
+        }
+    }
+}
+
+pub mod partial_date_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<PartialDate>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        match &scalar {
+            x if x.is_null() => Ok(None),
+            Scalar::Str(s)
+                if is_null_token(s)
+                    || UNKNOWN_DATES
+                        .iter()
+                        .any(|u| u.eq_ignore_ascii_case(s.trim())) =>
+            {
+                Ok(None)
+            }
+            _ => from_scalar(scalar).map(Some),
+        }
+    }
+
+    pub fn serialize<S>(val: &Option<PartialDate>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => serializer.serialize_str(&val.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub mod us {
+        pub use super::deserialize;
+        use super::{format_us, PartialDate, Serializer};
+
+        pub fn serialize<S>(val: &Option<PartialDate>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            match val {
+                Some(val) => serializer.serialize_str(&format_us(val)),
+                None => serializer.serialize_none(),
+            }
+        }
+    }
+}
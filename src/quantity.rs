@@ -0,0 +1,530 @@
+use crate::Scalar;
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dimension {
+    Mass,
+    Length,
+    Time,
+    Volume,
+    MassRate,
+    WeightBasedRate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Unit {
+    Kilogram,
+    Gram,
+    Milligram,
+    Microgram,
+    Pound,
+    Ounce,
+    Meter,
+    Centimeter,
+    Millimeter,
+    Inch,
+    Foot,
+    Hour,
+    Minute,
+    Second,
+    Liter,
+    Milliliter,
+    MilligramPerHour,
+    MicrogramPerMinute,
+    MicrogramPerKilogramPerMinute,
+    MicrogramPerKilogramPerHour,
+    MilligramPerKilogramPerHour,
+}
+
+impl Unit {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Unit::Kilogram => "kg",
+            Unit::Gram => "g",
+            Unit::Milligram => "mg",
+            Unit::Microgram => "mcg",
+            Unit::Pound => "lb",
+            Unit::Ounce => "oz",
+            Unit::Meter => "m",
+            Unit::Centimeter => "cm",
+            Unit::Millimeter => "mm",
+            Unit::Inch => "in",
+            Unit::Foot => "ft",
+            Unit::Hour => "h",
+            Unit::Minute => "min",
+            Unit::Second => "s",
+            Unit::Liter => "L",
+            Unit::Milliliter => "mL",
+            Unit::MilligramPerHour => "mg/h",
+            Unit::MicrogramPerMinute => "mcg/min",
+            Unit::MicrogramPerKilogramPerMinute => "mcg/kg/min",
+            Unit::MicrogramPerKilogramPerHour => "mcg/kg/h",
+            Unit::MilligramPerKilogramPerHour => "mg/kg/h",
+        }
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        match self {
+            Unit::Kilogram
+            | Unit::Gram
+            | Unit::Milligram
+            | Unit::Microgram
+            | Unit::Pound
+            | Unit::Ounce => Dimension::Mass,
+            Unit::Meter | Unit::Centimeter | Unit::Millimeter | Unit::Inch | Unit::Foot => {
+                Dimension::Length
+            }
+            Unit::Hour | Unit::Minute | Unit::Second => Dimension::Time,
+            Unit::Liter | Unit::Milliliter => Dimension::Volume,
+            Unit::MilligramPerHour | Unit::MicrogramPerMinute => Dimension::MassRate,
+            Unit::MicrogramPerKilogramPerMinute
+            | Unit::MicrogramPerKilogramPerHour
+            | Unit::MilligramPerKilogramPerHour => Dimension::WeightBasedRate,
+        }
+    }
+
+    // Multiplier into the SI base unit: kg, m, s, m³, kg/s and (kg/kg)/s
+    pub fn factor(&self) -> f64 {
+        match self {
+            Unit::Kilogram => 1.0,
+            Unit::Gram => 1e-3,
+            Unit::Milligram => 1e-6,
+            Unit::Microgram => 1e-9,
+            Unit::Pound => 0.453_592_37,
+            Unit::Ounce => 0.028_349_523_125,
+            Unit::Meter => 1.0,
+            Unit::Centimeter => 1e-2,
+            Unit::Millimeter => 1e-3,
+            Unit::Inch => 0.0254,
+            Unit::Foot => 0.3048,
+            Unit::Hour => 3600.0,
+            Unit::Minute => 60.0,
+            Unit::Second => 1.0,
+            Unit::Liter => 1e-3,
+            Unit::Milliliter => 1e-6,
+            Unit::MilligramPerHour => 1e-6 / 3600.0,
+            Unit::MicrogramPerMinute => 1e-9 / 60.0,
+            Unit::MicrogramPerKilogramPerMinute => 1e-9 / 60.0,
+            Unit::MicrogramPerKilogramPerHour => 1e-9 / 3600.0,
+            Unit::MilligramPerKilogramPerHour => 1e-6 / 3600.0,
+        }
+    }
+
+    // "m" is minutes when a time is expected, otherwise meters
+    pub fn from_symbol(symbol: &str, hint: Option<Dimension>) -> Option<Unit> {
+        let lower = symbol.trim().to_lowercase();
+        Some(match lower.as_str() {
+            "kg" | "kgs" | "kilogram" | "kilograms" => Unit::Kilogram,
+            "g" | "gm" | "gram" | "grams" => Unit::Gram,
+            "mg" | "milligram" | "milligrams" => Unit::Milligram,
+            "mcg" | "µg" | "μg" | "ug" | "microgram" | "micrograms" => Unit::Microgram,
+            "lb" | "lbs" | "pound" | "pounds" | "#" => Unit::Pound,
+            "oz" | "ounce" | "ounces" => Unit::Ounce,
+            "m" if hint == Some(Dimension::Time) => Unit::Minute,
+            "m" | "meter" | "meters" | "metre" | "metres" => Unit::Meter,
+            "cm" | "centimeter" | "centimeters" => Unit::Centimeter,
+            "mm" | "millimeter" | "millimeters" => Unit::Millimeter,
+            "in" | "inch" | "inches" | "\"" | "''" => Unit::Inch,
+            "ft" | "foot" | "feet" | "'" => Unit::Foot,
+            "h" | "hr" | "hrs" | "hour" | "hours" => Unit::Hour,
+            "min" | "mins" | "minute" | "minutes" => Unit::Minute,
+            "s" | "sec" | "secs" | "second" | "seconds" => Unit::Second,
+            "l" | "liter" | "liters" | "litre" | "litres" => Unit::Liter,
+            "ml" | "cc" | "milliliter" | "milliliters" => Unit::Milliliter,
+            "mg/h" | "mg/hr" => Unit::MilligramPerHour,
+            "mcg/min" | "µg/min" | "ug/min" => Unit::MicrogramPerMinute,
+            "mcg/kg/min" | "µg/kg/min" | "ug/kg/min" => Unit::MicrogramPerKilogramPerMinute,
+            "mcg/kg/h" | "mcg/kg/hr" | "µg/kg/h" | "ug/kg/hr" => Unit::MicrogramPerKilogramPerHour,
+            "mg/kg/h" | "mg/kg/hr" => Unit::MilligramPerKilogramPerHour,
+            _ => return None,
+        })
+    }
+
+    // The unit a bare trailing number refers to, as in 5'10 or 1h 25
+    fn next_smaller(&self) -> Option<Unit> {
+        match self {
+            Unit::Foot => Some(Unit::Inch),
+            Unit::Pound => Some(Unit::Ounce),
+            Unit::Hour => Some(Unit::Minute),
+            Unit::Minute => Some(Unit::Second),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.symbol())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quantity {
+    // Kept in `unit` as read, so "1.5 mg" reads back as 1.5 rather than 1.5000000000000002
+    value: f64,
+    unit: Unit,
+}
+
+impl Quantity {
+    pub fn new(value: f64, unit: Unit) -> Self {
+        Quantity { value, unit }
+    }
+
+    pub fn si_value(&self) -> f64 {
+        self.value * self.unit.factor()
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn unit(&self) -> Unit {
+        self.unit
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        self.unit.dimension()
+    }
+
+    pub fn value_in(&self, unit: Unit) -> Option<f64> {
+        if unit == self.unit {
+            Some(self.value)
+        } else if unit.dimension() == self.dimension() {
+            Some(self.si_value() / unit.factor())
+        } else {
+            None
+        }
+    }
+
+    pub fn convert(&self, unit: Unit) -> Option<Quantity> {
+        self.value_in(unit).map(|value| Quantity::new(value, unit))
+    }
+
+    // Accepts "72 kg", "158lb", "5'10\"", "5 ft 10 in", "50 mcg/kg/min", "1.5e3 mg" and "1h 25m";
+    // a bare number is read as `default_unit` when one is given
+    pub fn parse(
+        s: &str,
+        dimension: Option<Dimension>,
+        default_unit: Option<Unit>,
+    ) -> Result<Quantity, String> {
+        let mut rest = s.trim();
+        let mut base = 0.0;
+        let mut components = Vec::new();
+        let mut first_unit: Option<Unit> = None;
+        let mut last_unit: Option<Unit> = None;
+
+        if rest.is_empty() {
+            return Err("empty measurement".to_string());
+        }
+
+        while !rest.is_empty() {
+            let number_len = number_len(rest);
+            let value: f64 = rest[..number_len]
+                .parse()
+                .map_err(|_| format!("invalid measurement: {}", s))?;
+            rest = rest[number_len..].trim_start();
+
+            let symbol_len = rest
+                .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+                .unwrap_or(rest.len());
+            let symbol = &rest[..symbol_len];
+            rest = rest[symbol_len..].trim_start();
+
+            let hint = first_unit.map(|u| u.dimension()).or(dimension);
+            let unit = if symbol.is_empty() {
+                match last_unit {
+                    Some(last) => last.next_smaller(),
+                    None if rest.is_empty() => default_unit,
+                    None => None,
+                }
+                .ok_or_else(|| format!("missing unit in measurement: {}", s))?
+            } else {
+                Unit::from_symbol(symbol, hint)
+                    .ok_or_else(|| format!("unknown unit {:?} in measurement: {}", symbol, s))?
+            };
+
+            if let Some(expected) = hint {
+                if unit.dimension() != expected {
+                    return Err(format!(
+                        "expected a {:?} measurement, found {}: {}",
+                        expected, unit, s
+                    ));
+                }
+            }
+
+            base += value * unit.factor();
+            components.push(value);
+            first_unit = first_unit.or(Some(unit));
+            last_unit = Some(unit);
+        }
+
+        let unit = first_unit.expect("at least one component was parsed");
+        Ok(match components.as_slice() {
+            [value] => Quantity::new(*value, unit),
+            _ => Quantity::new(base / unit.factor(), unit),
+        })
+    }
+
+    pub fn format_in(&self, unit: Unit) -> Option<String> {
+        self.value_in(unit)
+            .map(|value| format!("{} {}", format_value(value), unit))
+    }
+}
+
+// The length of the number at the start of `s`, sign and exponent included, e.g. "-1.5e3"
+// in "-1.5e3mg". An "e" not followed by digits is left for the unit.
+fn number_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let digits = |from: usize| {
+        from + bytes[from..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit() || **b == b'.')
+            .count()
+    };
+
+    let start = if s.starts_with(['-', '+']) { 1 } else { 0 };
+    let mantissa = digits(start);
+    if mantissa > start && matches!(bytes.get(mantissa), Some(b'e') | Some(b'E')) {
+        let sign = if matches!(bytes.get(mantissa + 1), Some(b'-') | Some(b'+')) {
+            mantissa + 2
+        } else {
+            mantissa + 1
+        };
+        let exponent = sign
+            + bytes[sign..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit())
+                .count();
+        if exponent > sign {
+            return exponent;
+        }
+    }
+    mantissa
+}
+
+// The shortest form that reads back as the same value
+fn format_value(value: f64) -> String {
+    format!("{}", value)
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", format_value(self.value()), self.unit)
+    }
+}
+
+impl FromStr for Quantity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Quantity::parse(s, None, None)
+    }
+}
+
+pub trait Measure {
+    const DIMENSION: Dimension;
+    const DEFAULT_UNIT: Unit;
+}
+
+pub struct Weight;
+
+impl Measure for Weight {
+    const DIMENSION: Dimension = Dimension::Mass;
+    const DEFAULT_UNIT: Unit = Unit::Kilogram;
+}
+
+pub struct Height;
+
+impl Measure for Height {
+    const DIMENSION: Dimension = Dimension::Length;
+    const DEFAULT_UNIT: Unit = Unit::Centimeter;
+}
+
+pub struct Dose;
+
+impl Measure for Dose {
+    const DIMENSION: Dimension = Dimension::Mass;
+    const DEFAULT_UNIT: Unit = Unit::Milligram;
+}
+
+pub struct DoseRate;
+
+impl Measure for DoseRate {
+    const DIMENSION: Dimension = Dimension::WeightBasedRate;
+    const DEFAULT_UNIT: Unit = Unit::MicrogramPerKilogramPerMinute;
+}
+
+pub struct Elapsed;
+
+impl Measure for Elapsed {
+    const DIMENSION: Dimension = Dimension::Time;
+    const DEFAULT_UNIT: Unit = Unit::Minute;
+}
+
+pub trait UnitMarker {
+    const UNIT: Unit;
+}
+
+pub mod units {
+    use super::{Unit, UnitMarker};
+
+    macro_rules! unit_markers {
+        ($($name:ident => $unit:ident),* $(,)?) => {
+            $(
+                pub struct $name;
+
+                impl UnitMarker for $name {
+                    const UNIT: Unit = Unit::$unit;
+                }
+            )*
+        };
+    }
+
+    unit_markers! {
+        Kg => Kilogram,
+        G => Gram,
+        Mg => Milligram,
+        Mcg => Microgram,
+        Lb => Pound,
+        Oz => Ounce,
+        M => Meter,
+        Cm => Centimeter,
+        Mm => Millimeter,
+        In => Inch,
+        Ft => Foot,
+        H => Hour,
+        Min => Minute,
+        S => Second,
+        L => Liter,
+        Ml => Milliliter,
+        MgPerH => MilligramPerHour,
+        McgPerMin => MicrogramPerMinute,
+        McgPerKgPerMin => MicrogramPerKilogramPerMinute,
+        McgPerKgPerH => MicrogramPerKilogramPerHour,
+        MgPerKgPerH => MilligramPerKilogramPerHour,
+    }
+}
+
+fn from_scalar<M, E>(scalar: Scalar) -> Result<Quantity, E>
+where
+    M: Measure,
+    E: Error,
+{
+    match scalar {
+        Scalar::Str(s) => {
+            Quantity::parse(&s, Some(M::DIMENSION), Some(M::DEFAULT_UNIT)).map_err(E::custom)
+        }
+        x => match x.as_f64() {
+            Some(value) => Ok(Quantity::new(value, M::DEFAULT_UNIT)),
+            None => Err(E::invalid_type(x.unexpected(), &"a measurement")),
+        },
+    }
+}
+
+fn write<U, S>(val: &Quantity, serializer: S) -> Result<S::Ok, S::Error>
+where
+    U: UnitMarker,
+    S: Serializer,
+{
+    match val.format_in(U::UNIT) {
+        Some(formatted) => serializer.serialize_str(&formatted),
+        None => Err(serde::ser::Error::custom(format!(
+            "cannot express {} in {}",
+            val,
+            U::UNIT
+        ))),
+    }
+}
+
+pub mod measurement {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Quantity, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = Scalar::deserialize(deserializer)?.to_string();
+        s.parse().map_err(D::Error::custom)
+    }
+
+    // e.g. `#[serde(deserialize_with = "measurement::deserialize_as::<Weight, _>")]`
+    pub fn deserialize_as<'de, M, D>(deserializer: D) -> Result<Quantity, D::Error>
+    where
+        M: Measure,
+        D: Deserializer<'de>,
+    {
+        from_scalar::<M, D::Error>(Scalar::deserialize(deserializer)?)
+    }
+
+    pub fn serialize<S>(val: &Quantity, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&val.to_string())
+    }
+
+    // e.g. `#[serde(serialize_with = "measurement::serialize_in::<units::Lb, _>")]`
+    pub fn serialize_in<U, S>(val: &Quantity, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        U: UnitMarker,
+        S: Serializer,
+    {
+        write::<U, S>(val, serializer)
+    }
+}
+
+pub mod measurement_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<Quantity>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            Ok(None)
+        } else {
+            scalar
+                .to_string()
+                .parse()
+                .map(Some)
+                .map_err(D::Error::custom)
+        }
+    }
+
+    pub fn deserialize_as<'de, M, D>(deserializer: D) -> Result<Option<Quantity>, D::Error>
+    where
+        M: Measure,
+        D: Deserializer<'de>,
+    {
+        let scalar = Scalar::deserialize(deserializer)?;
+        if scalar.is_null() {
+            Ok(None)
+        } else {
+            from_scalar::<M, D::Error>(scalar).map(Some)
+        }
+    }
+
+    pub fn serialize<S>(val: &Option<Quantity>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => serializer.serialize_str(&val.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn serialize_in<U, S>(val: &Option<Quantity>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        U: UnitMarker,
+        S: Serializer,
+    {
+        match val {
+            Some(val) => write::<U, S>(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
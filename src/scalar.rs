@@ -0,0 +1,676 @@
+use crate::is_null_token;
+use serde::{
+    de::{
+        self,
+        value::{MapDeserializer, SeqDeserializer},
+        IntoDeserializer, MapAccess, SeqAccess, Unexpected, Visitor,
+    },
+    forward_to_deserialize_any, Deserialize, Deserializer,
+};
+
+use std::{cell::Cell, convert::TryFrom, fmt, marker::PhantomData};
+
+thread_local! {
+    static TEXT_CELLS: Cell<bool> = const { Cell::new(false) };
+}
+
+// Cells are read with the type their format gives them, as from serde_json or calamine.
+// Within `with_text_cells`, as in the `FromCsv` loaders, a cell is read as its original text,
+// so "007" and "1.50" survive instead of becoming csv's guesses 7 and 1.5
+#[derive(Debug, Clone, PartialEq)]
+pub enum Scalar {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f64),
+    Str(String),
+    Empty,
+}
+
+impl Scalar {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Scalar::Empty => true,
+            Scalar::Str(s) => s.is_empty(),
+            _ => false,
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        match self {
+            Scalar::Empty => true,
+            Scalar::Str(s) => is_null_token(s),
+            _ => false,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Scalar::Int(i) => Some(*i),
+            Scalar::UInt(u) => i64::try_from(*u).ok(),
+            Scalar::Float(f) => float_to_i64(*f),
+            Scalar::Str(s) => {
+                let trimmed = s.trim();
+                trimmed
+                    .parse::<i64>()
+                    .ok()
+                    .or_else(|| trimmed.parse::<f64>().ok().and_then(float_to_i64))
+            }
+            Scalar::Bool(_) | Scalar::Empty => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Scalar::Int(i) => Some(*i as f64),
+            Scalar::UInt(u) => Some(*u as f64),
+            Scalar::Float(f) => Some(*f),
+            Scalar::Str(s) => s.trim().parse::<f64>().ok(),
+            Scalar::Bool(_) | Scalar::Empty => None,
+        }
+    }
+
+    pub fn unexpected(&self) -> Unexpected<'_> {
+        match self {
+            Scalar::Bool(b) => Unexpected::Bool(*b),
+            Scalar::Int(i) => Unexpected::Signed(*i),
+            Scalar::UInt(u) => Unexpected::Unsigned(*u),
+            Scalar::Float(f) => Unexpected::Float(*f),
+            Scalar::Str(s) => Unexpected::Str(s),
+            Scalar::Empty => Unexpected::Unit,
+        }
+    }
+}
+
+fn float_to_i64(f: f64) -> Option<i64> {
+    if f.fract() == 0.0 && f >= i64::MIN as f64 && f <= i64::MAX as f64 {
+        Some(f as i64)
+    } else {
+        None
+    }
+}
+
+impl fmt::Display for Scalar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Scalar::Bool(b) => write!(f, "{}", b),
+            Scalar::Int(i) => write!(f, "{}", i),
+            Scalar::UInt(u) => write!(f, "{}", u),
+            Scalar::Float(x) => write!(f, "{}", x),
+            Scalar::Str(s) => f.write_str(s),
+            Scalar::Empty => Ok(()),
+        }
+    }
+}
+
+struct ScalarVisitor;
+
+impl<'de> Visitor<'de> for ScalarVisitor {
+    type Value = Scalar;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string, number, or boolean")
+    }
+
+    fn visit_bool<E>(self, v: bool) -> Result<Self::Value, E> {
+        Ok(Scalar::Bool(v))
+    }
+
+    fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E> {
+        Ok(Scalar::Int(v))
+    }
+
+    fn visit_i128<E>(self, v: i128) -> Result<Self::Value, E> {
+        Ok(i64::try_from(v)
+            .map(Scalar::Int)
+            .unwrap_or_else(|_| Scalar::Str(v.to_string())))
+    }
+
+    fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Scalar::UInt(v))
+    }
+
+    fn visit_u128<E>(self, v: u128) -> Result<Self::Value, E> {
+        Ok(u64::try_from(v)
+            .map(Scalar::UInt)
+            .unwrap_or_else(|_| Scalar::Str(v.to_string())))
+    }
+
+    fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E> {
+        Ok(Scalar::Float(v))
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(Scalar::Str(v.to_string()))
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E> {
+        Ok(Scalar::Str(v))
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(Scalar::Str(String::from_utf8_lossy(v).into_owned()))
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(Scalar::Empty)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(Scalar::Empty)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Scalar::deserialize(deserializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Scalar {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if TEXT_CELLS.with(Cell::get) {
+            deserializer.deserialize_str(ScalarVisitor)
+        } else {
+            deserializer.deserialize_any(ScalarVisitor)
+        }
+    }
+}
+
+// Reads every cell as its original text within `f`, as the `FromCsv` loaders do.
+// Needed when deserializing with a `csv::Reader` directly, e.g.
+// `with_text_cells(|| reader.deserialize().collect::<Result<Vec<Record>, _>>())`
+pub fn with_text_cells<F, T>(f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let _guard = CellsGuard(TEXT_CELLS.with(|text| text.replace(true)));
+    f()
+}
+
+// Reads cells as typed values within `f`, even inside `with_text_cells`,
+// e.g. a JSON document held in a CSV cell, as `embedded_json` reads it
+pub fn with_typed_cells<F, T>(f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let _guard = CellsGuard(TEXT_CELLS.with(|text| text.replace(false)));
+    f()
+}
+
+struct CellsGuard(bool);
+
+impl Drop for CellsGuard {
+    fn drop(&mut self) {
+        TEXT_CELLS.with(|text| text.set(self.0));
+    }
+}
+
+pub struct ScalarDeserializer<E> {
+    scalar: Scalar,
+    marker: PhantomData<E>,
+}
+
+impl<'de, E> IntoDeserializer<'de, E> for Scalar
+where
+    E: de::Error,
+{
+    type Deserializer = ScalarDeserializer<E>;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        ScalarDeserializer {
+            scalar: self,
+            marker: PhantomData,
+        }
+    }
+}
+
+// Mirrors the csv deserializer by converting between strings and numbers
+// according to the requested type, so a field can be re-read as any `T`
+impl<'de, E> Deserializer<'de> for ScalarDeserializer<E>
+where
+    E: de::Error,
+{
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.scalar {
+            Scalar::Bool(b) => visitor.visit_bool(b),
+            Scalar::Int(i) => visitor.visit_i64(i),
+            Scalar::UInt(u) => visitor.visit_u64(u),
+            Scalar::Float(f) => visitor.visit_f64(f),
+            Scalar::Str(s) => visitor.visit_string(s),
+            Scalar::Empty => visitor.visit_unit(),
+        }
+    }
+
+    fn deserialize_bool<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match &self.scalar {
+            Scalar::Str(s) => match s.trim().parse() {
+                Ok(b) => visitor.visit_bool(b),
+                Err(_) => self.deserialize_any(visitor),
+            },
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_i64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match (&self.scalar, self.scalar.as_i64()) {
+            (Scalar::UInt(u), _) => visitor.visit_u64(*u),
+            (_, Some(i)) => visitor.visit_i64(i),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_u64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match &self.scalar {
+            Scalar::Str(s) => match s.trim().parse() {
+                Ok(u) => visitor.visit_u64(u),
+                Err(_) => self.deserialize_i64(visitor),
+            },
+            _ => self.deserialize_i64(visitor),
+        }
+    }
+
+    fn deserialize_f64<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.scalar.as_f64() {
+            Some(f) => visitor.visit_f64(f),
+            None => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_string(self.scalar.to_string())
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.scalar.is_null() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.scalar {
+            Scalar::Str(s) => visitor.visit_enum(s.into_deserializer()),
+            x => x.into_deserializer().deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_i8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_i64(visitor)
+    }
+
+    fn deserialize_i16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_i64(visitor)
+    }
+
+    fn deserialize_i32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_i64(visitor)
+    }
+
+    fn deserialize_u8<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_u16<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_u32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_u64(visitor)
+    }
+
+    fn deserialize_f32<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_char<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_string(visitor)
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 unit unit_struct bytes byte_buf seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+// A whole field held in memory, so it can be checked for null tokens before
+// deserializing it as any `T`
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Buffered {
+    Scalar(Scalar),
+    Seq(Vec<Buffered>),
+    Map(Vec<(Buffered, Buffered)>),
+}
+
+impl Buffered {
+    pub(crate) fn is_null(&self) -> bool {
+        match self {
+            Buffered::Scalar(scalar) => scalar.is_null(),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Buffered {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Buffered::Scalar(scalar) => scalar.fmt(f),
+            Buffered::Seq(items) => {
+                f.write_str("[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    item.fmt(f)?;
+                }
+                f.write_str("]")
+            }
+            Buffered::Map(entries) => {
+                f.write_str("{")?;
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                f.write_str("}")
+            }
+        }
+    }
+}
+
+struct BufferedVisitor;
+
+impl<'de> Visitor<'de> for BufferedVisitor {
+    type Value = Buffered;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+        ScalarVisitor.visit_bool(v).map(Buffered::Scalar)
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        ScalarVisitor.visit_i64(v).map(Buffered::Scalar)
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+        ScalarVisitor.visit_i128(v).map(Buffered::Scalar)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        ScalarVisitor.visit_u64(v).map(Buffered::Scalar)
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+        ScalarVisitor.visit_u128(v).map(Buffered::Scalar)
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+        ScalarVisitor.visit_f64(v).map(Buffered::Scalar)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        ScalarVisitor.visit_str(v).map(Buffered::Scalar)
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        ScalarVisitor.visit_string(v).map(Buffered::Scalar)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        ScalarVisitor.visit_bytes(v).map(Buffered::Scalar)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Buffered::Scalar(Scalar::Empty))
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(Buffered::Scalar(Scalar::Empty))
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Buffered::deserialize(deserializer)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Buffered::deserialize(deserializer)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut items = Vec::new();
+        while let Some(item) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Buffered::Seq(items))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entries = Vec::new();
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(Buffered::Map(entries))
+    }
+}
+
+// Reads cells the same way as `Scalar`, keeping their text within `with_text_cells`
+impl<'de> Deserialize<'de> for Buffered {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if TEXT_CELLS.with(Cell::get) {
+            deserializer.deserialize_str(BufferedVisitor)
+        } else {
+            deserializer.deserialize_any(BufferedVisitor)
+        }
+    }
+}
+
+pub(crate) struct BufferedDeserializer<E> {
+    value: Buffered,
+    marker: PhantomData<E>,
+}
+
+impl<'de, E> IntoDeserializer<'de, E> for Buffered
+where
+    E: de::Error,
+{
+    type Deserializer = BufferedDeserializer<E>;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        BufferedDeserializer {
+            value: self,
+            marker: PhantomData,
+        }
+    }
+}
+
+macro_rules! forward_to_scalar {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                match self.value {
+                    Buffered::Scalar(scalar) => scalar.into_deserializer().$method(visitor),
+                    _ => self.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+// Scalars are converted like `ScalarDeserializer` does; sequences and maps are replayed
+impl<'de, E> Deserializer<'de> for BufferedDeserializer<E>
+where
+    E: de::Error,
+{
+    type Error = E;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Buffered::Scalar(scalar) => scalar.into_deserializer().deserialize_any(visitor),
+            Buffered::Seq(items) => {
+                let mut seq = SeqDeserializer::new(items.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Buffered::Map(entries) => {
+                let mut map = MapDeserializer::new(entries.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.value.is_null() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.value {
+            Buffered::Scalar(scalar) => scalar
+                .into_deserializer()
+                .deserialize_enum(name, variants, visitor),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    forward_to_scalar! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_f32
+        deserialize_f64 deserialize_char deserialize_str deserialize_string
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 unit unit_struct bytes byte_buf seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
@@ -0,0 +1,171 @@
+use crate::is_null_token;
+use serde::{Deserialize, Deserializer, Serializer};
+use unicode_normalization::UnicodeNormalization;
+
+const ZERO_WIDTH: &[char] = &['\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}', '\u{FEFF}'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LetterCase {
+    Preserve,
+    Upper,
+    Lower,
+}
+
+pub trait TextCase {
+    const CASE: LetterCase;
+}
+
+pub struct KeepCase;
+
+impl TextCase for KeepCase {
+    const CASE: LetterCase = LetterCase::Preserve;
+}
+
+pub struct UpperCase;
+
+impl TextCase for UpperCase {
+    const CASE: LetterCase = LetterCase::Upper;
+}
+
+pub struct LowerCase;
+
+impl TextCase for LowerCase {
+    const CASE: LetterCase = LetterCase::Lower;
+}
+
+// Drops zero-width characters, then trims whitespace, non-breaking spaces included
+pub fn trim_text(s: &str) -> String {
+    s.replace(ZERO_WIDTH, "").trim().to_string()
+}
+
+// Applies NFC, drops zero-width characters, and turns every run of whitespace,
+// non-breaking spaces included, into a single space before trimming
+pub fn normalize_text(s: &str, case: LetterCase) -> String {
+    let mut normalized = String::with_capacity(s.len());
+    let mut pending_space = false;
+    for c in s.nfc().filter(|c| !ZERO_WIDTH.contains(c)) {
+        if c.is_whitespace() {
+            pending_space = !normalized.is_empty();
+        } else {
+            if pending_space {
+                normalized.push(' ');
+                pending_space = false;
+            }
+            normalized.push(c);
+        }
+    }
+
+    match case {
+        LetterCase::Preserve => normalized,
+        LetterCase::Upper => normalized.to_uppercase(),
+        LetterCase::Lower => normalized.to_lowercase(),
+    }
+}
+
+fn non_null(s: String) -> Option<String> {
+    if s.is_empty() || is_null_token(&s) {
+        None
+    } else {
+        Some(s)
+    }
+}
+
+fn write_opt<S: Serializer>(val: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+    match val {
+        Some(val) => serializer.serialize_str(val),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub mod trimmed_string {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(trim_text(&String::deserialize(deserializer)?))
+    }
+
+    pub fn serialize<S>(val: &str, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(val)
+    }
+}
+
+// Whitespace-only cells are `None`, as are null tokens once trimmed
+pub mod trimmed_string_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(non_null(trim_text(&String::deserialize(deserializer)?)))
+    }
+
+    pub fn serialize<S>(val: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        write_opt(val, serializer)
+    }
+}
+
+pub mod normalized_string {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<String, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_as::<KeepCase, D>(deserializer)
+    }
+
+    // e.g. `#[serde(deserialize_with = "normalized_string::deserialize_as::<UpperCase, _>")]`
+    pub fn deserialize_as<'de, C, D>(deserializer: D) -> Result<String, D::Error>
+    where
+        C: TextCase,
+        D: Deserializer<'de>,
+    {
+        Ok(normalize_text(&String::deserialize(deserializer)?, C::CASE))
+    }
+
+    pub fn serialize<S>(val: &str, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(val)
+    }
+}
+
+pub mod normalized_string_opt {
+    use super::*;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserialize_as::<KeepCase, D>(deserializer)
+    }
+
+    pub fn deserialize_as<'de, C, D>(deserializer: D) -> Result<Option<String>, D::Error>
+    where
+        C: TextCase,
+        D: Deserializer<'de>,
+    {
+        Ok(non_null(normalize_text(
+            &String::deserialize(deserializer)?,
+            C::CASE,
+        )))
+    }
+
+    pub fn serialize<S>(val: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        write_opt(val, serializer)
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{is_null_token, trim_text, Scalar};
+use regex::Regex;
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+use std::{cell::RefCell, collections::HashMap, fmt};
+
+thread_local! {
+    static PATTERNS: RefCell<HashMap<&'static str, Regex>> = RefCell::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharClass {
+    Digits,
+    Letters,
+    Alphanumeric,
+    AsciiAlphanumeric,
+    // Printable ASCII, spaces included
+    AsciiPrintable,
+    // Exactly the listed characters
+    Only(&'static str),
+}
+
+impl CharClass {
+    pub fn allows(&self, c: char) -> bool {
+        match self {
+            CharClass::Digits => c.is_ascii_digit(),
+            CharClass::Letters => c.is_alphabetic(),
+            CharClass::Alphanumeric => c.is_alphanumeric(),
+            CharClass::AsciiAlphanumeric => c.is_ascii_alphanumeric(),
+            CharClass::AsciiPrintable => c == ' ' || c.is_ascii_graphic(),
+            CharClass::Only(chars) => chars.contains(c),
+        }
+    }
+}
+
+impl fmt::Display for CharClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CharClass::Digits => f.write_str("digits"),
+            CharClass::Letters => f.write_str("letters"),
+            CharClass::Alphanumeric => f.write_str("letters and digits"),
+            CharClass::AsciiAlphanumeric => f.write_str("ASCII letters and digits"),
+            CharClass::AsciiPrintable => f.write_str("printable ASCII"),
+            CharClass::Only(chars) => write!(f, "one of {:?}", chars),
+        }
+    }
+}
+
+// Constraints checked by `validated_string` once the value is trimmed, e.g.
+// impl StringRule for ProcedureCode {
+//     const MAX_LENGTH: Option<usize> = Some(5);
+//     const CHARACTERS: Option<CharClass> = Some(CharClass::AsciiAlphanumeric);
+// }
+pub trait StringRule {
+    // Lengths count characters, not bytes
+    const MIN_LENGTH: usize = 0;
+    const MAX_LENGTH: Option<usize> = None;
+    // Must match the whole value
+    const PATTERN: Option<&'static str> = None;
+    const CHARACTERS: Option<CharClass> = None;
+    // When not empty, the value must be one of these
+    const ALLOWED: &'static [&'static str] = &[];
+    const IGNORE_CASE: bool = false;
+}
+
+fn matches_pattern(pattern: &'static str, s: &str) -> Result<bool, String> {
+    PATTERNS.with(|patterns| {
+        let mut patterns = patterns.borrow_mut();
+        if !patterns.contains_key(pattern) {
+            let regex = Regex::new(&format!("^(?:{})$", pattern))
+                .map_err(|err| format!("invalid pattern {:?}: {}", pattern, err))?;
+            patterns.insert(pattern, regex);
+        }
+        Ok(patterns[pattern].is_match(s))
+    })
+}
+
+pub fn validate_string<R: StringRule>(s: &str) -> Result<(), String> {
+    let length = s.chars().count();
+    if length < R::MIN_LENGTH {
+        return Err(format!(
+            "{:?} is shorter than {} characters",
+            s,
+            R::MIN_LENGTH
+        ));
+    }
+    if let Some(max) = R::MAX_LENGTH {
+        if length > max {
+            return Err(format!("{:?} is longer than {} characters", s, max));
+        }
+    }
+
+    if let Some(class) = R::CHARACTERS {
+        if let Some(c) = s.chars().find(|&c| !class.allows(c)) {
+            return Err(format!("{:?} contains {:?}; expected only {}", s, c, class));
+        }
+    }
+
+    if let Some(pattern) = R::PATTERN {
+        let matched =
+            matches_pattern(pattern, s).map_err(|err| format!("cannot check {:?}: {}", s, err))?;
+        if !matched {
+            return Err(format!("{:?} does not match {:?}", s, pattern));
+        }
+    }
+
+    if !R::ALLOWED.is_empty() {
+        let allowed = R::ALLOWED.iter().any(|allowed| {
+            if R::IGNORE_CASE {
+                allowed.to_lowercase() == s.to_lowercase()
+            } else {
+                *allowed == s
+            }
+        });
+        if !allowed {
+            return Err(format!("{:?} is not one of {}", s, R::ALLOWED.join(", ")));
+        }
+    }
+
+    Ok(())
+}
+
+// Typed cells are checked as their text, so a rule for digits accepts a number cell
+fn text_of(scalar: Scalar) -> String {
+    match scalar {
+        Scalar::Str(s) => trim_text(&s),
+        x => x.to_string(),
+    }
+}
+
+pub mod validated_string {
+    use super::*;
+
+    // e.g. `#[serde(deserialize_with = "validated_string::deserialize_as::<ProcedureCode, _>")]`
+    pub fn deserialize_as<'de, R, D>(deserializer: D) -> Result<String, D::Error>
+    where
+        R: StringRule,
+        D: Deserializer<'de>,
+    {
+        let s = text_of(Scalar::deserialize(deserializer)?);
+        validate_string::<R>(&s).map_err(D::Error::custom)?;
+        Ok(s)
+    }
+
+    pub fn serialize<S>(val: &str, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(val)
+    }
+}
+
+pub mod validated_string_opt {
+    use super::*;
+
+    pub fn deserialize_as<'de, R, D>(deserializer: D) -> Result<Option<String>, D::Error>
+    where
+        R: StringRule,
+        D: Deserializer<'de>,
+    {
+        let s = text_of(Scalar::deserialize(deserializer)?);
+        if s.is_empty() || is_null_token(&s) {
+            return Ok(None);
+        }
+        validate_string::<R>(&s).map_err(D::Error::custom)?;
+        Ok(Some(s))
+    }
+
+    pub fn serialize<S>(val: &Option<String>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => serializer.serialize_str(val),
+            None => serializer.serialize_none(),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::load::with_current;
+use chrono::{Datelike, Local, NaiveDate};
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YearPivot {
+    // Two-digit years land in the hundred years starting at this year;
+    // chrono's `%y` rule is `Fixed(1970)`
+    Fixed(i32),
+    // Two-digit years land in the hundred years ending this many years after today
+    SlidingWindow { years_ahead: i32 },
+}
+
+impl Default for YearPivot {
+    fn default() -> Self {
+        YearPivot::Fixed(1970)
+    }
+}
+
+impl YearPivot {
+    pub fn resolve(&self, two_digit_year: u32) -> i32 {
+        self.resolve_relative_to(two_digit_year, today())
+    }
+
+    // Resolves a sliding window against `reference` instead of today
+    pub fn resolve_relative_to(&self, two_digit_year: u32, reference: NaiveDate) -> i32 {
+        let start = match *self {
+            YearPivot::Fixed(start) => start,
+            YearPivot::SlidingWindow { years_ahead } => reference.year() + years_ahead - 99,
+        };
+        let year = start - start.rem_euclid(100) + (two_digit_year % 100) as i32;
+        if year < start {
+            year + 100
+        } else {
+            year
+        }
+    }
+}
+
+pub trait YearWindow {
+    const PIVOT: YearPivot;
+}
+
+// e.g. `#[serde(deserialize_with = "mm_dd_yy_date::deserialize_as::<FixedPivot<1930>, _>")]`
+pub struct FixedPivot<const START: i32>;
+
+impl<const START: i32> YearWindow for FixedPivot<START> {
+    const PIVOT: YearPivot = YearPivot::Fixed(START);
+}
+
+pub struct SlidingPivot<const YEARS_AHEAD: i32>;
+
+impl<const YEARS_AHEAD: i32> YearWindow for SlidingPivot<YEARS_AHEAD> {
+    const PIVOT: YearPivot = YearPivot::SlidingWindow {
+        years_ahead: YEARS_AHEAD,
+    };
+}
+
+fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+pub(crate) fn current_year_pivot() -> YearPivot {
+    with_current(|options| options.and_then(|options| options.year_pivot)).unwrap_or_default()
+}
+
+// Moves `date` by whole centuries into the hundred years ending `years_ahead` after `reference`,
+// for fixing two-digit years against another field once a record is loaded
+pub fn adjust_century(
+    date: NaiveDate,
+    reference: NaiveDate,
+    years_ahead: i32,
+) -> Option<NaiveDate> {
+    let pivot = YearPivot::SlidingWindow { years_ahead };
+    let year = pivot.resolve_relative_to(date.year().rem_euclid(100) as u32, reference);
+    date.with_year(year)
+}
+
+// Accepts "MM/DD/YY" and "MM/DD/YYYY"; four-digit years are taken as written
+pub fn parse_mm_dd_yy(s: &str, pivot: YearPivot) -> Option<NaiveDate> {
+    let parts: Vec<&str> = s.trim().split('/').collect();
+    let (month, day, year) = match parts.as_slice() {
+        [month, day, year] => (month.parse().ok()?, day.parse().ok()?, *year),
+        _ => return None,
+    };
+    if !year.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let year = match year.len() {
+        2 => pivot.resolve(year.parse().ok()?),
+        4 => year.parse().ok()?,
+        _ => return None,
+    };
+    NaiveDate::from_ymd_opt(year, month, day)
+}
+
+// Two-digit years are read in the hundred years ending today, and four-digit future dates fail
+pub fn parse_birthdate(s: &str) -> Option<NaiveDate> {
+    let today = today();
+    let pivot = YearPivot::SlidingWindow { years_ahead: 0 };
+    let trimmed = s.trim();
+    let date = parse_mm_dd_yy(trimmed, pivot)?;
+    if date <= today {
+        Some(date)
+    } else if trimmed.rsplit('/').next().map(str::len) == Some(2) {
+        date.with_year(date.year() - 100)
+    } else {
+        None
+    }
+}
+
+pub mod mm_dd_yy_birthdate {
+    use super::*;
+
+    const FORMAT: &str = "%m/%d/%Y";
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<NaiveDate, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        parse_birthdate(&s).ok_or_else(|| D::Error::custom(format!("invalid birthdate: {}", s)))
+    }
+
+    // Always writes four-digit years so the value reads back unambiguously
+    pub fn serialize<S>(val: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&val.format(FORMAT).to_string())
+    }
+}
+
+pub mod mm_dd_yy_birthdate_opt {
+    use super::*;
+    use crate::is_null_token;
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        if is_null_token(&s) {
+            return Ok(None);
+        }
+        parse_birthdate(&s)
+            .map(Some)
+            .ok_or_else(|| D::Error::custom(format!("invalid birthdate: {}", s)))
+    }
+
+    pub fn serialize<S>(val: &Option<NaiveDate>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => mm_dd_yy_birthdate::serialize(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
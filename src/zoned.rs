@@ -0,0 +1,317 @@
+use crate::is_null_token;
+use chrono::{DateTime, Duration, FixedOffset, LocalResult, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmbiguousTime {
+    // The first occurrence, i.e. still on daylight time when clocks fall back
+    Earliest,
+    Latest,
+    Reject,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonexistentTime {
+    // Reads the time with the offset in effect before the gap, so 02:30 becomes 03:30
+    ShiftForward,
+    Reject,
+}
+
+// e.g. `struct Central; impl Zone for Central { const TZ: Tz = chrono_tz::America::Chicago; }`
+pub trait Zone {
+    const TZ: Tz;
+    const AMBIGUOUS: AmbiguousTime = AmbiguousTime::Earliest;
+    const NONEXISTENT: NonexistentTime = NonexistentTime::ShiftForward;
+}
+
+pub struct UtcZone;
+
+impl Zone for UtcZone {
+    const TZ: Tz = Tz::UTC;
+}
+
+pub trait DateTimeFormat {
+    const PARSE: &'static [&'static str];
+    const FORMAT: &'static str;
+}
+
+pub struct MmDdYyyyFormat;
+
+impl DateTimeFormat for MmDdYyyyFormat {
+    const PARSE: &'static [&'static str] =
+        &["%m/%d/%Y %H:%M:%S", "%m/%d/%Y %H:%M", "%m/%d/%y %H:%M"];
+    const FORMAT: &'static str = "%m/%d/%Y %H:%M:%S";
+}
+
+pub struct YyyyMmDdFormat;
+
+impl DateTimeFormat for YyyyMmDdFormat {
+    const PARSE: &'static [&'static str] = &["%Y-%m-%d %H:%M:%S"];
+    const FORMAT: &'static str = "%Y-%m-%d %H:%M:%S";
+}
+
+pub struct VaFormat;
+
+impl DateTimeFormat for VaFormat {
+    const PARSE: &'static [&'static str] = &[
+        "%m/%d/%Y %I:%M:%S %p",
+        "%m/%d/%Y %H:%M:%S",
+        "%m/%d/%Y %H:%M",
+    ];
+    const FORMAT: &'static str = "%m/%d/%Y %I:%M:%S %p";
+}
+
+pub struct MssqlFormat;
+
+impl DateTimeFormat for MssqlFormat {
+    const PARSE: &'static [&'static str] = &["%Y-%m-%d %H:%M:%S.%3f", "%Y-%m-%d %H:%M:%S"];
+    const FORMAT: &'static str = "%Y-%m-%d %H:%M:%S.%3f";
+}
+
+pub fn resolve_local(
+    naive: &NaiveDateTime,
+    tz: Tz,
+    ambiguous: AmbiguousTime,
+    nonexistent: NonexistentTime,
+) -> Result<DateTime<Tz>, String> {
+    match tz.from_local_datetime(naive) {
+        LocalResult::Single(dt) => Ok(dt),
+        LocalResult::Ambiguous(earliest, latest) => match ambiguous {
+            AmbiguousTime::Earliest => Ok(earliest),
+            AmbiguousTime::Latest => Ok(latest),
+            AmbiguousTime::Reject => Err(format!("ambiguous local time in {}: {}", tz, naive)),
+        },
+        LocalResult::None => match nonexistent {
+            NonexistentTime::ShiftForward => {
+                let before = tz
+                    .offset_from_utc_datetime(&(*naive - Duration::days(1)))
+                    .fix();
+                let utc = *naive - Duration::seconds(before.local_minus_utc() as i64);
+                Ok(tz.from_utc_datetime(&utc))
+            }
+            NonexistentTime::Reject => Err(format!("nonexistent local time in {}: {}", tz, naive)),
+        },
+    }
+}
+
+// Splits a trailing "Z", "UTC", "GMT", "+05", "-0500" or "-05:00" from the time portion
+fn split_offset(s: &str) -> (&str, Option<FixedOffset>) {
+    let s = s.trim();
+    for suffix in &["UTC", "GMT", "Z"] {
+        if let Some(rest) = s.strip_suffix(suffix) {
+            if rest.ends_with(|c: char| c.is_ascii_digit() || c == ' ') {
+                return (rest.trim_end(), FixedOffset::east_opt(0));
+            }
+        }
+    }
+
+    if let Some(i) = s.rfind(['+', '-']) {
+        let (rest, offset) = (&s[..i], &s[i + 1..]);
+        let digits: String = offset.chars().filter(|c| *c != ':').collect();
+        if rest.contains(':')
+            && digits.chars().all(|c| c.is_ascii_digit())
+            && (digits.len() == 2 || digits.len() == 4)
+            && (offset.len() == digits.len() || offset.find(':') == Some(2))
+        {
+            let hours: i32 = digits[..2].parse().unwrap_or(0);
+            let minutes: i32 = digits[2..].parse().unwrap_or(0);
+            let seconds = (hours * 60 + minutes) * 60;
+            let seconds = if s[i..].starts_with('-') {
+                -seconds
+            } else {
+                seconds
+            };
+            if let Some(offset) = FixedOffset::east_opt(seconds) {
+                return (rest.trim_end(), Some(offset));
+            }
+        }
+    }
+
+    (s, None)
+}
+
+fn parse_naive<F: DateTimeFormat>(s: &str) -> Option<NaiveDateTime> {
+    F::PARSE
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+}
+
+// An explicit offset wins; otherwise the time is local to `Z`
+pub fn parse_zoned<F, Z>(s: &str) -> Result<DateTime<FixedOffset>, String>
+where
+    F: DateTimeFormat,
+    Z: Zone,
+{
+    let (local, offset) = split_offset(s);
+    let naive = parse_naive::<F>(local).ok_or_else(|| format!("invalid datetime: {}", s))?;
+    match offset {
+        Some(offset) => offset
+            .from_local_datetime(&naive)
+            .single()
+            .ok_or_else(|| format!("invalid datetime: {}", s)),
+        None => resolve_local(&naive, Z::TZ, Z::AMBIGUOUS, Z::NONEXISTENT)
+            .map(|dt| dt.with_timezone(&dt.offset().fix())),
+    }
+}
+
+fn deserialize_zoned<'de, F, Z, D>(
+    deserializer: D,
+) -> Result<Option<DateTime<FixedOffset>>, D::Error>
+where
+    F: DateTimeFormat,
+    Z: Zone,
+    D: Deserializer<'de>,
+{
+    let s = String::deserialize(deserializer)?;
+    if is_null_token(&s) {
+        Ok(None)
+    } else {
+        parse_zoned::<F, Z>(&s).map(Some).map_err(D::Error::custom)
+    }
+}
+
+pub mod utc_datetime {
+    use super::*;
+
+    // e.g. `#[serde(deserialize_with = "utc_datetime::deserialize_in::<MmDdYyyyFormat, Central, _>")]`
+    pub fn deserialize_in<'de, F, Z, D>(deserializer: D) -> Result<DateTime<Utc>, D::Error>
+    where
+        F: DateTimeFormat,
+        Z: Zone,
+        D: Deserializer<'de>,
+    {
+        deserialize_zoned::<F, Z, D>(deserializer)?
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok_or_else(|| D::Error::custom("missing datetime"))
+    }
+
+    // RFC 3339, e.g. "2021-03-14T12:30:00Z"
+    pub fn serialize<S>(val: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&val.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
+    }
+
+    // Writes the local time in `Z` using the format it was read with
+    pub fn serialize_in<F, Z, S>(val: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        F: DateTimeFormat,
+        Z: Zone,
+        S: Serializer,
+    {
+        serializer.serialize_str(&val.with_timezone(&Z::TZ).format(F::FORMAT).to_string())
+    }
+}
+
+pub mod utc_datetime_opt {
+    use super::*;
+
+    pub fn deserialize_in<'de, F, Z, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+    where
+        F: DateTimeFormat,
+        Z: Zone,
+        D: Deserializer<'de>,
+    {
+        Ok(deserialize_zoned::<F, Z, D>(deserializer)?.map(|dt| dt.with_timezone(&Utc)))
+    }
+
+    pub fn serialize<S>(val: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => utc_datetime::serialize(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn serialize_in<F, Z, S>(
+        val: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        F: DateTimeFormat,
+        Z: Zone,
+        S: Serializer,
+    {
+        match val {
+            Some(val) => utc_datetime::serialize_in::<F, Z, S>(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+pub mod offset_datetime {
+    use super::*;
+
+    pub fn deserialize_in<'de, F, Z, D>(deserializer: D) -> Result<DateTime<FixedOffset>, D::Error>
+    where
+        F: DateTimeFormat,
+        Z: Zone,
+        D: Deserializer<'de>,
+    {
+        deserialize_zoned::<F, Z, D>(deserializer)?
+            .ok_or_else(|| D::Error::custom("missing datetime"))
+    }
+
+    // RFC 3339, e.g. "2021-03-14T07:30:00-05:00"
+    pub fn serialize<S>(val: &DateTime<FixedOffset>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&val.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true))
+    }
+
+    // Writes the format it was read with followed by the offset, e.g. "03/14/2021 07:30:00 -05:00"
+    pub fn serialize_as<F, S>(val: &DateTime<FixedOffset>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        F: DateTimeFormat,
+        S: Serializer,
+    {
+        serializer.serialize_str(&format!("{} {}", val.format(F::FORMAT), val.format("%:z")))
+    }
+}
+
+pub mod offset_datetime_opt {
+    use super::*;
+
+    pub fn deserialize_in<'de, F, Z, D>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<FixedOffset>>, D::Error>
+    where
+        F: DateTimeFormat,
+        Z: Zone,
+        D: Deserializer<'de>,
+    {
+        deserialize_zoned::<F, Z, D>(deserializer)
+    }
+
+    pub fn serialize<S>(
+        val: &Option<DateTime<FixedOffset>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match val {
+            Some(val) => offset_datetime::serialize(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn serialize_as<F, S>(
+        val: &Option<DateTime<FixedOffset>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        F: DateTimeFormat,
+        S: Serializer,
+    {
+        match val {
+            Some(val) => offset_datetime::serialize_as::<F, S>(val, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use common::to_csv;
+use deserialize::{enum_from_code, enum_from_code_opt, EnumCode, FromCsv, LoadOptions};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Triage {
+    Resuscitation,
+    Emergent,
+    Urgent,
+}
+
+impl EnumCode for Triage {
+    const CODES: &'static [(&'static str, Self)] = &[
+        ("01", Triage::Resuscitation),
+        ("1", Triage::Emergent),
+        ("1.50", Triage::Urgent),
+    ];
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Arrival {
+    #[serde(with = "enum_from_code")]
+    triage: Triage,
+    #[serde(with = "enum_from_code_opt")]
+    transfer: Option<Triage>,
+}
+
+impl FromCsv for Arrival {}
+
+#[test]
+fn matches_codes_as_written() {
+    let input = "triage,transfer\n01,1\n1,01\n1.50,\n1.5,1.5\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Arrival::from_csv_reader(input.as_bytes()));
+    let records = records.unwrap();
+
+    // "1.5" is not the code "1.50"
+    assert_eq!(report.loaded, 3, "{:?}", report);
+    assert_eq!(report.errors.len(), 1, "{:?}", report);
+    assert_eq!(report.errors[0].row, 4);
+
+    assert_eq!(records[0].triage, Triage::Resuscitation);
+    assert_eq!(records[0].transfer, Some(Triage::Emergent));
+    assert_eq!(records[1].triage, Triage::Emergent);
+    assert_eq!(records[1].transfer, Some(Triage::Resuscitation));
+    assert_eq!(records[2].triage, Triage::Urgent);
+    assert_eq!(records[2].transfer, None);
+
+    assert_eq!(to_csv(&records), "triage,transfer\n01,1\n1,01\n1.50,\n");
+}
+
+#[test]
+fn matches_typed_numbers_by_value() {
+    let record: Arrival = serde_json::from_str(r#"{"triage": 1.0, "transfer": 1}"#).unwrap();
+    assert_eq!(record.triage, Triage::Emergent);
+    assert_eq!(record.transfer, Some(Triage::Emergent));
+}
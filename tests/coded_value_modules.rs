@@ -0,0 +1,85 @@
+mod common;
+
+use common::to_csv;
+use deserialize::{
+    enum_from_code_or_unknown, enum_from_id_or_unknown, CodedValue, EnumCode, FromCsv, LoadOptions,
+};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Route {
+    Oral,
+    Intravenous,
+}
+
+impl EnumCode for Route {
+    const CODES: &'static [(&'static str, Self)] =
+        &[("PO", Route::Oral), ("IV", Route::Intravenous)];
+}
+
+impl TryFrom<i32> for Route {
+    type Error = ();
+
+    fn try_from(id: i32) -> Result<Self, ()> {
+        match id {
+            1 => Ok(Route::Oral),
+            2 => Ok(Route::Intravenous),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<&Route> for i32 {
+    fn from(route: &Route) -> i32 {
+        match route {
+            Route::Oral => 1,
+            Route::Intravenous => 2,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Dose {
+    #[serde(with = "enum_from_code_or_unknown")]
+    route: CodedValue<Route>,
+    #[serde(with = "enum_from_id_or_unknown")]
+    route_id: CodedValue<Route>,
+}
+
+impl FromCsv for Dose {}
+
+#[test]
+fn keeps_unknown_codes_as_written() {
+    let input = "route,route_id\nPO,1\n007,007\n1.50,1.50\nIV,02\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Dose::from_csv_reader(input.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+
+    assert_eq!(records[0].route, CodedValue::Known(Route::Oral));
+    assert_eq!(records[0].route_id, CodedValue::Known(Route::Oral));
+    assert_eq!(records[1].route, CodedValue::Unknown("007".to_string()));
+    assert_eq!(records[1].route_id, CodedValue::Unknown("007".to_string()));
+    assert_eq!(records[2].route, CodedValue::Unknown("1.50".to_string()));
+    assert_eq!(records[2].route_id, CodedValue::Unknown("1.50".to_string()));
+    assert_eq!(records[3].route_id, CodedValue::Known(Route::Intravenous));
+
+    // Both columns read the same enum, so each unknown code is listed once per column
+    let codes: Vec<_> = report
+        .unknown_codes
+        .iter()
+        .map(|unknown| (unknown.code.as_str(), unknown.rows.clone()))
+        .collect();
+    assert_eq!(
+        codes,
+        vec![("007", vec![2, 2]), ("1.50", vec![3, 3])],
+        "{:?}",
+        report
+    );
+
+    assert_eq!(
+        to_csv(&records),
+        "route,route_id\nPO,1\n007,007\n1.50,1.50\nIV,2\n"
+    );
+}
@@ -0,0 +1,26 @@
+// Shared by the integration tests; each test crate uses only some of these
+#![allow(dead_code)]
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+// A single-column CSV, with every value quoted so empty cells aren't skipped as blank lines
+pub fn csv_of(header: &str, values: &[&str]) -> String {
+    let mut csv = format!("{}\n", header);
+    for value in values {
+        csv.push_str(&format!("\"{}\"\n", value));
+    }
+    csv
+}
+
+pub fn to_csv<T: Serialize>(records: &[T]) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for record in records {
+        writer.serialize(record).unwrap();
+    }
+    String::from_utf8(writer.into_inner().unwrap()).unwrap()
+}
+
+pub fn ymd(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
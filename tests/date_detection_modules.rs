@@ -0,0 +1,143 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::{to_csv, ymd};
+use deserialize::{
+    any_date, any_date_opt, detect_date_format, parse_any_datetime, AmbiguousDateColumn,
+    DetectedDateFormat, FromCsv, LoadOptions,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Visit {
+    #[serde(with = "any_date")]
+    booked: NaiveDate,
+    #[serde(with = "any_date")]
+    seen: NaiveDate,
+    #[serde(with = "any_date_opt")]
+    billed: Option<NaiveDate>,
+}
+
+impl FromCsv for Visit {}
+
+const VISITS: &str = "booked,seen,billed\n\
+                      12/01/2021,12/01/2021,03/04/2021\n\
+                      12/31/2021,31/12/2021,\n";
+
+fn check(records: Vec<Visit>, report: deserialize::LoadReport) {
+    assert_eq!(report.loaded, 2, "{:?}", report);
+    assert!(report.errors.is_empty(), "{:?}", report);
+
+    assert_eq!(records[0].booked, ymd(2021, 12, 1));
+    assert_eq!(records[0].seen, ymd(2021, 1, 12));
+    assert_eq!(records[1].seen, ymd(2021, 12, 31));
+    assert_eq!(records[0].billed, Some(ymd(2021, 3, 4)));
+    assert_eq!(records[1].billed, None);
+
+    assert_eq!(
+        report.ambiguous_date_columns,
+        vec![AmbiguousDateColumn {
+            column: "billed".to_string(),
+            format: "%m/%d/%Y",
+        }]
+    );
+    assert_eq!(
+        to_csv(&records),
+        "booked,seen,billed\n\
+         2021-12-01,2021-01-12,2021-03-04\n\
+         2021-12-31,2021-12-31,\n"
+    );
+}
+
+#[test]
+fn detects_date_formats_per_column() {
+    let (records, report) = LoadOptions::new()
+        .detect_date_formats()
+        .run_with_report(|| Visit::from_csv_reader(VISITS.as_bytes()));
+    check(records.unwrap(), report);
+}
+
+#[test]
+fn detects_date_formats_per_column_from_bytes() {
+    let (records, report) = LoadOptions::new()
+        .detect_date_formats()
+        .run_with_report(|| Visit::from_bytes(VISITS.as_bytes()));
+    check(records.unwrap(), report);
+}
+
+#[test]
+fn reads_ranked_formats_without_detection() {
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Visit::from_csv_reader(VISITS.as_bytes()));
+    let records = records.unwrap();
+    assert_eq!(report.loaded, 2, "{:?}", report);
+    assert!(report.ambiguous_date_columns.is_empty());
+    assert_eq!(records[0].seen, ymd(2021, 12, 1));
+}
+
+#[test]
+fn rejects_numbers_that_are_not_dates() {
+    let input = "booked,seen,billed\n12/01/2021,007,\n12/31/2021,1.50,\n";
+    let (records, report) = LoadOptions::new()
+        .detect_date_formats()
+        .run_with_report(|| Visit::from_csv_reader(input.as_bytes()));
+    assert!(records.unwrap().is_empty());
+    assert_eq!(report.errors.len(), 2, "{:?}", report);
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Admission {
+    #[serde(with = "any_date")]
+    admitted: NaiveDate,
+    #[serde(with = "any_date")]
+    discharged: NaiveDate,
+}
+
+impl FromCsv for Admission {}
+
+#[test]
+fn reads_two_digit_years_as_years() {
+    assert_eq!(
+        parse_any_datetime("03/04/21").map(|dt| dt.date()),
+        Some(ymd(2021, 3, 4))
+    );
+    assert_eq!(
+        parse_any_datetime("11/12/20").map(|dt| dt.date()),
+        Some(ymd(2020, 11, 12))
+    );
+    assert_eq!(
+        detect_date_format(["12/31/21", "01/15/22"]),
+        Some(DetectedDateFormat {
+            format: "%m/%d/%y",
+            ambiguous: false,
+        })
+    );
+    assert_eq!(
+        detect_date_format(["2021/03/04", "2021/12/31"]),
+        Some(DetectedDateFormat {
+            format: "%Y/%m/%d",
+            ambiguous: false,
+        })
+    );
+    assert_eq!(parse_any_datetime("0021/03/04"), None);
+}
+
+#[test]
+fn detects_two_digit_and_year_first_columns() {
+    let input = "admitted,discharged\n03/04/21,2021/03/09\n12/31/21,2022/01/02\n";
+    let (records, report) = LoadOptions::new()
+        .detect_date_formats()
+        .run_with_report(|| Admission::from_csv_reader(input.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    assert!(report.ambiguous_date_columns.is_empty(), "{:?}", report);
+    let records = records.unwrap();
+
+    assert_eq!(records[0].admitted, ymd(2021, 3, 4));
+    assert_eq!(records[0].discharged, ymd(2021, 3, 9));
+    assert_eq!(records[1].admitted, ymd(2021, 12, 31));
+    assert_eq!(records[1].discharged, ymd(2022, 1, 2));
+    assert_eq!(
+        to_csv(&records),
+        "admitted,discharged\n2021-03-04,2021-03-09\n2021-12-31,2022-01-02\n"
+    );
+}
@@ -0,0 +1,217 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::{csv_of, to_csv};
+use deserialize::{mm_dd_yyyy_date, mm_dd_yyyy_date_opt, mssql_date, FromCsv, LoadOptions};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct UsDate {
+    #[serde(with = "mm_dd_yyyy_date")]
+    date: NaiveDate,
+}
+
+impl FromCsv for UsDate {}
+
+#[derive(Debug, Deserialize)]
+struct StrictUsDate {
+    #[serde(deserialize_with = "mm_dd_yyyy_date::deserialize_strict")]
+    date: NaiveDate,
+}
+
+impl FromCsv for StrictUsDate {}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct OptionalUsDate {
+    #[serde(with = "mm_dd_yyyy_date_opt")]
+    date: Option<NaiveDate>,
+}
+
+impl FromCsv for OptionalUsDate {}
+
+#[derive(Debug, Deserialize)]
+struct StrictOptionalUsDate {
+    #[serde(deserialize_with = "mm_dd_yyyy_date_opt::deserialize_strict")]
+    date: Option<NaiveDate>,
+}
+
+impl FromCsv for StrictOptionalUsDate {}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct MssqlDate {
+    #[serde(with = "mssql_date")]
+    date: NaiveDate,
+}
+
+impl FromCsv for MssqlDate {}
+
+#[derive(Debug, Deserialize)]
+struct StrictMssqlDate {
+    #[serde(deserialize_with = "mssql_date::deserialize_strict")]
+    date: NaiveDate,
+}
+
+impl FromCsv for StrictMssqlDate {}
+
+fn march_14() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2021, 3, 14).unwrap()
+}
+
+const US_FORMATS: &[&str] = &[
+    "03/14/2021",
+    "3/14/2021",
+    "03/14/21",
+    " 03/14/2021 ",
+    "03/14/2021 07:30:15",
+    "03/14/2021 07:30:15.123",
+    "03/14/2021 07:30",
+    "03/14/2021 07:30:15 AM",
+    "03/14/2021 7:30 PM",
+    "03/14/21 07:30:15",
+    "03/14/21 07:30",
+    "03/14/21 07:30:15 PM",
+    "03/14/21 7:30 AM",
+    "03/14/2021 00:00:00",
+];
+
+const MSSQL_FORMATS: &[&str] = &[
+    "2021-03-14",
+    "2021-03-14 07:30:15.123",
+    "2021-03-14 07:30:15",
+    "2021-03-14 07:30",
+    "2021-03-14T07:30:15.123",
+    "2021-03-14T07:30",
+    "2021-03-14 00:00:00.000",
+];
+
+#[test]
+fn mm_dd_yyyy_date_accepts_dates_and_datetimes() {
+    let (records, report) = LoadOptions::new()
+        .run_with_report(|| UsDate::from_csv_reader(csv_of("date", US_FORMATS).as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+    assert_eq!(records.len(), US_FORMATS.len());
+    for (record, input) in records.iter().zip(US_FORMATS) {
+        assert_eq!(record.date, march_14(), "input {:?}", input);
+    }
+}
+
+#[test]
+fn mm_dd_yyyy_date_rejects_garbage() {
+    let input = csv_of(
+        "date",
+        &["2021-03-14", "14/03/2021", "03/14", "not a date", ""],
+    );
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| UsDate::from_csv_reader(input.as_bytes()));
+    assert!(records.unwrap().is_empty());
+    assert_eq!(report.errors.len(), 5);
+}
+
+#[test]
+fn mm_dd_yyyy_date_strict_rejects_times_of_day() {
+    let input = csv_of(
+        "date",
+        &[
+            "03/14/2021",
+            "03/14/2021 00:00:00",
+            "03/14/2021 00:00",
+            "03/14/2021 07:30:15",
+        ],
+    );
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| StrictUsDate::from_csv_reader(input.as_bytes()));
+    let records = records.unwrap();
+    assert_eq!(records.len(), 3);
+    assert!(records.iter().all(|record| record.date == march_14()));
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].row, 4);
+    assert!(report.errors[0].message.contains("without a time of day"));
+}
+
+#[test]
+fn mm_dd_yyyy_date_serializes_date_only() {
+    let records =
+        UsDate::from_csv_reader(csv_of("date", &["03/14/2021 07:30:15"]).as_bytes()).unwrap();
+    assert_eq!(to_csv(&records), "date\n03/14/2021\n");
+}
+
+#[test]
+fn mm_dd_yyyy_date_opt_accepts_dates_and_datetimes() {
+    let mut values = US_FORMATS.to_vec();
+    values.push("");
+    values.push("garbage");
+    let records = OptionalUsDate::from_csv_reader(csv_of("date", &values).as_bytes()).unwrap();
+    assert_eq!(records.len(), values.len());
+    for (record, input) in records.iter().zip(US_FORMATS) {
+        assert_eq!(record.date, Some(march_14()), "input {:?}", input);
+    }
+    assert_eq!(records[US_FORMATS.len()].date, None);
+    assert_eq!(records[US_FORMATS.len() + 1].date, None);
+}
+
+#[test]
+fn mm_dd_yyyy_date_opt_round_trips() {
+    let input = csv_of("date", &["03/14/2021", ""]);
+    let records = OptionalUsDate::from_csv_reader(input.as_bytes()).unwrap();
+    assert_eq!(records[0].date, Some(march_14()));
+    assert_eq!(records[1].date, None);
+    assert_eq!(to_csv(&records), "date\n03/14/2021\n\"\"\n");
+}
+
+#[test]
+fn mm_dd_yyyy_date_opt_strict() {
+    let input = csv_of(
+        "date",
+        &["03/14/2021", "", "NULL", "03/14/2021 07:30", "garbage"],
+    );
+    let (records, report) = LoadOptions::new()
+        .run_with_report(|| StrictOptionalUsDate::from_csv_reader(input.as_bytes()));
+    let dates: Vec<_> = records
+        .unwrap()
+        .into_iter()
+        .map(|record| record.date)
+        .collect();
+    assert_eq!(dates, vec![Some(march_14()), None, None]);
+    let failed: Vec<_> = report.errors.iter().map(|error| error.row).collect();
+    assert_eq!(failed, vec![4, 5]);
+}
+
+#[test]
+fn mssql_date_accepts_dates_and_datetimes() {
+    let (records, report) = LoadOptions::new()
+        .run_with_report(|| MssqlDate::from_csv_reader(csv_of("date", MSSQL_FORMATS).as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+    assert_eq!(records.len(), MSSQL_FORMATS.len());
+    for (record, input) in records.iter().zip(MSSQL_FORMATS) {
+        assert_eq!(record.date, march_14(), "input {:?}", input);
+    }
+}
+
+#[test]
+fn mssql_date_strict_rejects_times_of_day() {
+    let input = csv_of(
+        "date",
+        &[
+            "2021-03-14",
+            "2021-03-14 00:00:00.000",
+            "2021-03-14 07:30:15.123",
+        ],
+    );
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| StrictMssqlDate::from_csv_reader(input.as_bytes()));
+    let records = records.unwrap();
+    assert_eq!(records.len(), 2);
+    assert!(records.iter().all(|record| record.date == march_14()));
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].row, 3);
+}
+
+#[test]
+fn mssql_date_serializes_date_only() {
+    let records =
+        MssqlDate::from_csv_reader(csv_of("date", &["2021-03-14 07:30:15.123"]).as_bytes())
+            .unwrap();
+    assert_eq!(to_csv(&records), "date\n2021-03-14\n");
+}
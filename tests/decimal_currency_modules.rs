@@ -0,0 +1,94 @@
+#![cfg(feature = "rust_decimal")]
+
+mod common;
+
+use common::to_csv;
+use deserialize::{
+    decimal_currency, decimal_currency_opt, parse_currency, AccountingStyle, FromCsv, LoadOptions,
+};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Charge {
+    #[serde(with = "decimal_currency")]
+    amount: Decimal,
+    #[serde(
+        deserialize_with = "decimal_currency_opt::deserialize",
+        serialize_with = "decimal_currency_opt::serialize_as::<AccountingStyle, _>"
+    )]
+    adjustment: Option<Decimal>,
+}
+
+impl FromCsv for Charge {}
+
+fn decimal(s: &str) -> Decimal {
+    Decimal::from_str(s).unwrap()
+}
+
+#[test]
+fn plain_numbers_stay_exact() {
+    let input = "amount,adjustment\n\
+                 12345678901234567.89,0.30000000000000004441\n\
+                 00123,1.50\n\
+                 \"$1,234.56\",(12.50)\n\
+                 1e3,\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Charge::from_csv_reader(input.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+    assert_eq!(records[0].amount, decimal("12345678901234567.89"));
+    assert_eq!(
+        records[0].adjustment,
+        Some(decimal("0.30000000000000004441"))
+    );
+    assert_eq!(records[1].amount, decimal("123"));
+    assert_eq!(records[1].adjustment, Some(decimal("1.50")));
+    assert_eq!(records[2].amount, decimal("1234.56"));
+    assert_eq!(records[2].adjustment, Some(decimal("-12.50")));
+    assert_eq!(records[3].amount, decimal("1000"));
+    assert_eq!(records[3].adjustment, None);
+}
+
+#[test]
+fn round_trips_through_csv() {
+    let input = "amount,adjustment\n12345678901234567.89,1234.5\n00123,(1.50)\n";
+    let records = Charge::from_csv_reader(input.as_bytes()).unwrap();
+    assert_eq!(
+        to_csv(&records),
+        "amount,adjustment\n\
+         $12345678901234567.89,\"$1,234.50\"\n\
+         $123.00,($1.50)\n"
+    );
+}
+
+#[test]
+fn typed_floats_use_their_shortest_form() {
+    let record: Charge = serde_json::from_str(r#"{"amount": 0.1, "adjustment": 12}"#).unwrap();
+    assert_eq!(record.amount, decimal("0.1"));
+    assert_eq!(record.adjustment, Some(decimal("12")));
+}
+
+#[test]
+fn takes_a_single_sign_marker() {
+    assert_eq!(parse_currency("-$5"), Some(decimal("-5")));
+    assert_eq!(parse_currency("($5)"), Some(decimal("-5")));
+    assert_eq!(parse_currency("5-"), Some(decimal("-5")));
+    assert_eq!(parse_currency("+5"), Some(decimal("5")));
+    assert_eq!(parse_currency("--5"), None);
+    assert_eq!(parse_currency("-(5)"), None);
+    assert_eq!(parse_currency("(-5)"), None);
+    assert_eq!(parse_currency("-5-"), None);
+    assert_eq!(parse_currency("$-$5"), None);
+}
+
+#[test]
+fn rejects_european_decimal_commas() {
+    let input = "amount,adjustment\n\"1,234.56\",\n\"1.234,56 €\",\n\"12,50\",\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Charge::from_csv_reader(input.as_bytes()));
+    assert_eq!(records.unwrap()[0].amount, decimal("1234.56"));
+    let failed: Vec<_> = report.errors.iter().map(|error| error.row).collect();
+    assert_eq!(failed, vec![2], "{:?}", report);
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use chrono::Duration;
+use common::{csv_of, to_csv};
+use deserialize::{duration, duration_opt, FromCsv, LoadOptions};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct CaseLength {
+    #[serde(with = "duration::hh_mm")]
+    length: Duration,
+}
+
+impl FromCsv for CaseLength {}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Turnover {
+    #[serde(with = "duration_opt")]
+    turnover: Option<Duration>,
+}
+
+impl FromCsv for Turnover {}
+
+#[test]
+fn bare_numbers_are_minutes() {
+    let input = csv_of(
+        "length",
+        &[
+            "85", "0085", "1.5", "1.0", "1.50 min", "01:25", "1h25m", "PT1H25M",
+        ],
+    );
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| CaseLength::from_csv_reader(input.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let lengths: Vec<_> = records
+        .unwrap()
+        .iter()
+        .map(|record| record.length)
+        .collect();
+    assert_eq!(
+        lengths,
+        vec![
+            Duration::minutes(85),
+            Duration::minutes(85),
+            Duration::seconds(90),
+            Duration::minutes(1),
+            Duration::seconds(90),
+            Duration::minutes(85),
+            Duration::minutes(85),
+            Duration::minutes(85),
+        ]
+    );
+}
+
+#[test]
+fn out_of_range_values_are_errors() {
+    let input = csv_of(
+        "length",
+        &[
+            "9999999999999:00",
+            "99999999999999999999",
+            "1e300",
+            "PT1e9H",
+            "01:25",
+        ],
+    );
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| CaseLength::from_csv_reader(input.as_bytes()));
+    assert_eq!(records.unwrap().len(), 1);
+    let failed: Vec<_> = report.errors.iter().map(|error| error.row).collect();
+    assert_eq!(failed, vec![1, 2, 3, 4]);
+}
+
+#[test]
+fn round_trips_through_csv() {
+    let records = CaseLength::from_csv_reader(csv_of("length", &["85", "00:05"]).as_bytes());
+    assert_eq!(to_csv(&records.unwrap()), "length\n01:25\n00:05\n");
+
+    let input = csv_of("turnover", &["1.5", "", "PT2H"]);
+    let records = Turnover::from_csv_reader(input.as_bytes()).unwrap();
+    assert_eq!(to_csv(&records), "turnover\nPT1M30S\n\"\"\nPT2H\n");
+}
+
+#[test]
+fn typed_fractions_below_one_are_fractions_of_a_day() {
+    let json = r#"[{"length": 0.0625}, {"length": 85}, {"length": 85.0}, {"length": 1.5}, {"length": "1.5"}]"#;
+    let records: Vec<CaseLength> = serde_json::from_str(json).unwrap();
+    let lengths: Vec<_> = records.iter().map(|record| record.length).collect();
+    assert_eq!(
+        lengths,
+        vec![
+            Duration::minutes(90),
+            Duration::minutes(85),
+            Duration::minutes(85),
+            Duration::seconds(90),
+            Duration::seconds(90),
+        ]
+    );
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "serde_json")]
+
+mod common;
+
+use common::to_csv;
+use deserialize::{embedded_json, embedded_json_opt, FromCsv, LoadOptions};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct Device {
+    model: String,
+    settings: Vec<u32>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Implant {
+    #[serde(with = "embedded_json")]
+    device: Device,
+    #[serde(with = "embedded_json_opt")]
+    notes: Option<serde_json::Value>,
+}
+
+impl FromCsv for Implant {}
+
+const INPUT: &str = "device,notes\n\
+                     \"{\"\"model\"\": \"\"007\"\", \"\"settings\"\": [1, 20]}\",\"{\"\"lead\"\":2.50}\"\n\
+                     \"{\"\"model\"\":\"\"X1\"\",\"\"settings\"\":[]}\",NULL\n";
+
+#[test]
+fn reads_json_cells_with_their_own_types() {
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Implant::from_csv_reader(INPUT.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+
+    assert_eq!(
+        records[0].device,
+        Device {
+            model: "007".to_string(),
+            settings: vec![1, 20]
+        }
+    );
+    assert_eq!(records[0].notes, Some(serde_json::json!({"lead": 2.5})));
+    assert!(records[1].device.settings.is_empty());
+    assert_eq!(records[1].notes, None);
+}
+
+#[test]
+fn rejects_cells_that_are_not_json() {
+    let input = "device,notes\n\
+                 model=X1,\"\"\n\
+                 \"{\"\"model\"\":\"\"X1\"\"}\",\"\"\n\
+                 \"{\"\"model\"\":\"\"X1\"\",\"\"settings\"\":[]}\",{oops\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Implant::from_csv_reader(input.as_bytes()));
+    assert!(records.unwrap().is_empty());
+    let failed: Vec<_> = report.errors.iter().map(|error| error.row).collect();
+    assert_eq!(failed, vec![1, 2, 3], "{:?}", report);
+    assert!(
+        report.errors[0].message.contains("invalid embedded JSON"),
+        "{:?}",
+        report
+    );
+}
+
+#[test]
+fn round_trips_as_compact_json() {
+    let records = Implant::from_csv_reader(INPUT.as_bytes()).unwrap();
+    let written = to_csv(&records);
+    assert_eq!(
+        written,
+        "device,notes\n\
+         \"{\"\"model\"\":\"\"007\"\",\"\"settings\"\":[1,20]}\",\"{\"\"lead\"\":2.5}\"\n\
+         \"{\"\"model\"\":\"\"X1\"\",\"\"settings\"\":[]}\",\n"
+    );
+    let reread = Implant::from_csv_reader(written.as_bytes()).unwrap();
+    assert_eq!(reread[0].device, records[0].device);
+    assert_eq!(reread[0].notes, records[0].notes);
+    assert_eq!(reread[1].notes, None);
+}
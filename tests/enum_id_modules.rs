@@ -0,0 +1,165 @@
+mod common;
+
+use common::to_csv;
+use deserialize::{enum_from_id, enum_from_id_opt, FromCsv, LoadOptions};
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Severity {
+    Minor,
+    Major,
+}
+
+impl TryFrom<u8> for Severity {
+    type Error = ();
+
+    fn try_from(id: u8) -> Result<Self, ()> {
+        match id {
+            1 => Ok(Severity::Minor),
+            2 => Ok(Severity::Major),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<&Severity> for u8 {
+    fn from(severity: &Severity) -> u8 {
+        match severity {
+            Severity::Minor => 1,
+            Severity::Major => 2,
+        }
+    }
+}
+
+// Ids past `i64::MAX`, as some source systems use for sentinel rows
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Source {
+    Manual,
+    Unassigned,
+}
+
+impl TryFrom<u64> for Source {
+    type Error = ();
+
+    fn try_from(id: u64) -> Result<Self, ()> {
+        match id {
+            1 => Ok(Source::Manual),
+            u64::MAX => Ok(Source::Unassigned),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<&Source> for u64 {
+    fn from(source: &Source) -> u64 {
+        match source {
+            Source::Manual => 1,
+            Source::Unassigned => u64::MAX,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Direction {
+    Debit,
+    Credit,
+}
+
+impl TryFrom<i16> for Direction {
+    type Error = ();
+
+    fn try_from(id: i16) -> Result<Self, ()> {
+        match id {
+            -1 => Ok(Direction::Debit),
+            1 => Ok(Direction::Credit),
+            _ => Err(()),
+        }
+    }
+}
+
+impl From<&Direction> for i16 {
+    fn from(direction: &Direction) -> i16 {
+        match direction {
+            Direction::Debit => -1,
+            Direction::Credit => 1,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Incident {
+    #[serde(
+        deserialize_with = "enum_from_id::deserialize_as::<u8, _, _>",
+        serialize_with = "enum_from_id::serialize_as::<u8, _, _>"
+    )]
+    severity: Severity,
+    #[serde(
+        deserialize_with = "enum_from_id::deserialize_as::<u64, _, _>",
+        serialize_with = "enum_from_id::serialize_as::<u64, _, _>"
+    )]
+    source: Source,
+    #[serde(
+        deserialize_with = "enum_from_id_opt::deserialize_as::<i16, _, _>",
+        serialize_with = "enum_from_id_opt::serialize_as::<i16, _, _>"
+    )]
+    direction: Option<Direction>,
+}
+
+impl FromCsv for Incident {}
+
+const INPUT: &str = "severity,source,direction\n\
+                     1,18446744073709551615,-1\n\
+                     02,1,1.0\n\
+                     2,1,7\n";
+
+#[test]
+fn reads_ids_of_each_width() {
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Incident::from_csv_reader(INPUT.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+
+    assert_eq!(records[0].severity, Severity::Minor);
+    assert_eq!(records[0].source, Source::Unassigned);
+    assert_eq!(records[0].direction, Some(Direction::Debit));
+    assert_eq!(records[1].severity, Severity::Major);
+    assert_eq!(records[1].source, Source::Manual);
+    assert_eq!(records[1].direction, Some(Direction::Credit));
+    // `enum_from_id_opt::deserialize_as` reads unknown ids as `None`
+    assert_eq!(records[2].direction, None);
+}
+
+#[test]
+fn rejects_ids_out_of_range_or_unknown() {
+    let input = "severity,source,direction\n\
+                 257,1,\"\"\n\
+                 3,1,\"\"\n\
+                 minor,1,\"\"\n\
+                 1,-1,\"\"\n\
+                 1,18446744073709551616,\"\"\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Incident::from_csv_reader(input.as_bytes()));
+    assert!(records.unwrap().is_empty());
+    let failed: Vec<_> = report.errors.iter().map(|error| error.row).collect();
+    assert_eq!(failed, vec![1, 2, 3, 4, 5], "{:?}", report);
+}
+
+#[test]
+fn round_trips_as_integers_of_each_width() {
+    let records = Incident::from_csv_reader(INPUT.as_bytes()).unwrap();
+    let written = to_csv(&records);
+    assert_eq!(
+        written,
+        "severity,source,direction\n\
+         1,18446744073709551615,-1\n\
+         2,1,1\n\
+         2,1,\n"
+    );
+    let reread = Incident::from_csv_reader(written.as_bytes()).unwrap();
+    for (reread, record) in reread.iter().zip(&records) {
+        assert_eq!(reread.severity, record.severity);
+        assert_eq!(reread.source, record.source);
+        assert_eq!(reread.direction, record.direction);
+    }
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use chrono::NaiveDateTime;
+use common::{to_csv, ymd};
+use deserialize::{
+    timestamp, timestamp_opt, DotNetTicks, FromCsv, LoadOptions, OleAutomationDate, Scalar,
+    TimestampScale, UnixMillis,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Event {
+    #[serde(with = "timestamp")]
+    seconds: NaiveDateTime,
+    #[serde(
+        deserialize_with = "timestamp::deserialize_as::<UnixMillis, _>",
+        serialize_with = "timestamp::serialize_as::<UnixMillis, _>"
+    )]
+    millis: NaiveDateTime,
+    #[serde(
+        deserialize_with = "timestamp::deserialize_as::<DotNetTicks, _>",
+        serialize_with = "timestamp::serialize_as::<DotNetTicks, _>"
+    )]
+    ticks: NaiveDateTime,
+    #[serde(
+        deserialize_with = "timestamp_opt::deserialize_as::<OleAutomationDate, _>",
+        serialize_with = "timestamp_opt::serialize_as::<OleAutomationDate, _>"
+    )]
+    ole: Option<NaiveDateTime>,
+}
+
+impl FromCsv for Event {}
+
+fn march_14_0730() -> NaiveDateTime {
+    ymd(2021, 3, 14).and_hms_opt(7, 30, 0).unwrap()
+}
+
+const INPUT: &str = "seconds,millis,ticks,ole\n\
+                     1615707000,1615707000000,637513038000000000,44269.3125\n\
+                     1615707000.5,1615707000500,637513038005000000,\"\"\n";
+
+#[test]
+fn reads_each_scale() {
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Event::from_csv_reader(INPUT.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+
+    let half_second = march_14_0730() + chrono::Duration::milliseconds(500);
+    assert_eq!(records[0].seconds, march_14_0730());
+    assert_eq!(records[0].millis, march_14_0730());
+    assert_eq!(records[0].ticks, march_14_0730());
+    assert_eq!(records[0].ole, Some(march_14_0730()));
+    assert_eq!(records[1].seconds, half_second);
+    assert_eq!(records[1].millis, half_second);
+    assert_eq!(records[1].ticks, half_second);
+    assert_eq!(records[1].ole, None);
+}
+
+#[test]
+fn rejects_non_numeric_timestamps_and_empty_scales() {
+    let input = "seconds,millis,ticks,ole\n\
+                 yesterday,1615707000000,637513038000000000,\"\"\n\
+                 1615707000,1615707000000,637513038000000000,9e99\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Event::from_csv_reader(input.as_bytes()));
+    assert!(records.unwrap().is_empty());
+    let failed: Vec<_> = report.errors.iter().map(|error| error.row).collect();
+    assert_eq!(failed, vec![1, 2], "{:?}", report);
+
+    let empty = TimestampScale::Unix { per_second: 0 };
+    assert_eq!(empty.from_i64(1615707000), None);
+    assert_eq!(empty.from_f64(1615707000.0), None);
+    assert_eq!(empty.from_scalar(&Scalar::Int(1615707000)), None);
+    assert_eq!(empty.to_i64(&march_14_0730()), None);
+    let finer_than_nanos = TimestampScale::Unix {
+        per_second: 10_000_000_000,
+    };
+    assert_eq!(finer_than_nanos.from_i64(1), None);
+    assert_eq!(finer_than_nanos.to_i64(&march_14_0730()), None);
+}
+
+#[test]
+fn round_trips_through_csv() {
+    let records = Event::from_csv_reader(INPUT.as_bytes()).unwrap();
+    assert_eq!(
+        to_csv(&records),
+        "seconds,millis,ticks,ole\n\
+         1615707000,1615707000000,637513038000000000,44269.3125\n\
+         1615707000,1615707000500,637513038005000000,\n"
+    );
+}
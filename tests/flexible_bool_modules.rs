@@ -0,0 +1,75 @@
+mod common;
+
+use common::csv_of;
+use deserialize::{flexible_bool, nullable_flexible_bool, FromCsv, LoadOptions};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+struct Lenient {
+    #[serde(deserialize_with = "flexible_bool::deserialize_lenient")]
+    flag: bool,
+}
+
+impl FromCsv for Lenient {}
+
+#[derive(Debug, Deserialize)]
+struct LenientOptional {
+    #[serde(deserialize_with = "nullable_flexible_bool::deserialize_lenient")]
+    flag: Option<bool>,
+}
+
+impl FromCsv for LenientOptional {}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct YesNo {
+    #[serde(with = "flexible_bool::yes_no")]
+    flag: bool,
+}
+
+impl FromCsv for YesNo {}
+
+#[test]
+fn lenient_accepts_more_spellings() {
+    let input = csv_of(
+        "flag",
+        &["Yes", "ON", "t", "checked", "1.0", "off", "F", "0.0", " n "],
+    );
+    let records = Lenient::from_csv_reader(input.as_bytes()).unwrap();
+    let flags: Vec<_> = records.iter().map(|record| record.flag).collect();
+    assert_eq!(
+        flags,
+        vec![true, true, true, true, true, false, false, false, false]
+    );
+}
+
+#[test]
+fn lenient_rejects_unrecognized_and_empty_values() {
+    let input = csv_of("flag", &["garbage", "2", "", "NULL", "00", "yes"]);
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Lenient::from_csv_reader(input.as_bytes()));
+    assert_eq!(records.unwrap().len(), 1);
+    let failed: Vec<_> = report.errors.iter().map(|error| error.row).collect();
+    assert_eq!(failed, vec![1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn lenient_optional_warns_on_unrecognized_values() {
+    let input = csv_of("flag", &["garbage", "", "Y", "00"]);
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| LenientOptional::from_csv_reader(input.as_bytes()));
+    let flags: Vec<_> = records.unwrap().iter().map(|record| record.flag).collect();
+    assert_eq!(flags, vec![None, None, Some(true), None]);
+    let warned: Vec<_> = report.warnings.iter().map(|warning| warning.row).collect();
+    assert_eq!(warned, vec![1, 4]);
+}
+
+#[test]
+fn yes_no_round_trips() {
+    let records = YesNo::from_csv_reader(csv_of("flag", &["y", "FALSE", "1"]).as_bytes()).unwrap();
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for record in &records {
+        writer.serialize(record).unwrap();
+    }
+    let output = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+    assert_eq!(output, "flag\nYes\nNo\nYes\n");
+}
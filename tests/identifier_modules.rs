@@ -0,0 +1,84 @@
+mod common;
+
+use common::to_csv;
+use deserialize::{
+    identifier, identifier_opt, normalize_identifier, AlphanumericId, FromCsv, LoadOptions,
+    NumericId, PaddedId,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Patient {
+    #[serde(with = "identifier")]
+    mrn: String,
+    #[serde(
+        deserialize_with = "identifier::deserialize_as::<PaddedId<8>, _>",
+        serialize_with = "identifier::serialize_as::<PaddedId<8>, _>"
+    )]
+    account: String,
+    #[serde(
+        deserialize_with = "identifier_opt::deserialize_as::<AlphanumericId, _>",
+        serialize_with = "identifier_opt::serialize_as::<AlphanumericId, _>"
+    )]
+    badge: Option<String>,
+}
+
+impl FromCsv for Patient {}
+
+#[test]
+fn normalizes_identifiers_from_text() {
+    let input = "mrn,account,badge\n\
+                 00123,00123,00123\n\
+                 1234567.0,1234567.00,1E5\n\
+                 123-45,0,\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Patient::from_csv_reader(input.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+
+    assert_eq!(records[0].mrn, "123");
+    assert_eq!(records[0].account, "00000123");
+    assert_eq!(records[0].badge.as_deref(), Some("00123"));
+    assert_eq!(records[1].mrn, "1234567");
+    assert_eq!(records[1].account, "01234567");
+    assert_eq!(records[1].badge.as_deref(), Some("1E5"));
+    assert_eq!(records[2].mrn, "12345");
+    assert_eq!(records[2].account, "00000000");
+    assert_eq!(records[2].badge, None);
+
+    assert_eq!(
+        to_csv(&records),
+        "mrn,account,badge\n\
+         123,00000123,00123\n\
+         1234567,01234567,1E5\n\
+         12345,00000000,\n"
+    );
+}
+
+#[test]
+fn reads_typed_float_cells_as_whole_numbers() {
+    let patient: Patient =
+        serde_json::from_str(r#"{"mrn": 1234567.0, "account": 123, "badge": 100000.0}"#).unwrap();
+    assert_eq!(patient.mrn, "1234567");
+    assert_eq!(patient.account, "00000123");
+    assert_eq!(patient.badge.as_deref(), Some("100000"));
+}
+
+#[test]
+fn rejects_decimals_and_signs() {
+    let input = "mrn,account,badge\n\
+                 123.45,1,\n\
+                 -5,1,\n\
+                 5,+5,\n\
+                 1,1,-AB\n\
+                 12.34.56,1,1.5\n\
+                 12.34.56,1,\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Patient::from_csv_reader(input.as_bytes()));
+    let failed: Vec<_> = report.errors.iter().map(|error| error.row).collect();
+    assert_eq!(failed, vec![1, 2, 3, 4, 5], "{:?}", report);
+    assert_eq!(records.unwrap()[0].mrn, "123456");
+    assert!(normalize_identifier::<NumericId>("123.45")
+        .unwrap_err()
+        .contains("decimal"));
+}
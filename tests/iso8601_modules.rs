@@ -0,0 +1,90 @@
+mod common;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use common::{to_csv, ymd};
+use deserialize::{
+    iso8601_date, iso8601_datetime_opt, iso8601_partial_date_opt, iso8601_utc_datetime,
+    parse_iso8601_datetime, parse_iso8601_offset_datetime, FromCsv, LoadOptions, PartialDate,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Observation {
+    #[serde(with = "iso8601_date")]
+    date: NaiveDate,
+    #[serde(with = "iso8601_datetime_opt")]
+    taken: Option<NaiveDateTime>,
+    #[serde(with = "iso8601_utc_datetime")]
+    recorded: DateTime<Utc>,
+    #[serde(with = "iso8601_partial_date_opt")]
+    onset: Option<PartialDate>,
+}
+
+impl FromCsv for Observation {}
+
+const INPUT: &str = "date,taken,recorded,onset\n\
+                     2021-03-14,2021-03-14 07:30,2021-03-14T07:30:00-05:00,2015\n\
+                     20210314,20210314T073000.5,2021-03-14t12:30:00Z,2015-03\n\
+                     2021-03-14,\"\",2021-03-14T12:30Z,\"\"\n";
+
+#[test]
+fn reads_extended_and_basic_forms() {
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Observation::from_csv_reader(INPUT.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+
+    let half_past_seven = ymd(2021, 3, 14).and_hms_opt(7, 30, 0).unwrap();
+    let noon_utc = Utc.with_ymd_and_hms(2021, 3, 14, 12, 30, 0).unwrap();
+    for record in &records {
+        assert_eq!(record.date, ymd(2021, 3, 14));
+        assert_eq!(record.recorded, noon_utc);
+    }
+    assert_eq!(records[0].taken, Some(half_past_seven));
+    assert_eq!(
+        records[1].taken,
+        Some(half_past_seven + chrono::Duration::milliseconds(500))
+    );
+    assert_eq!(records[2].taken, None);
+    assert_eq!(records[0].onset, PartialDate::new(2015, None, None));
+    assert_eq!(records[1].onset, PartialDate::new(2015, Some(3), None));
+    assert_eq!(records[2].onset, None);
+}
+
+#[test]
+fn rejects_other_forms_and_dropped_offsets() {
+    let input = "date,taken,recorded,onset\n\
+                 03/14/2021,\"\",2021-03-14T12:30Z,\"\"\n\
+                 2021-03-14,2021-03-14T07:30:00-05:00,2021-03-14T12:30Z,\"\"\n\
+                 2021-03-14,\"\",2021-03-14T12:30,\"\"\n\
+                 2021-03-14,\"\",2021-03-14T12:30Z,2015-3\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Observation::from_csv_reader(input.as_bytes()));
+    assert!(records.unwrap().is_empty());
+    let failed: Vec<_> = report.errors.iter().map(|error| error.row).collect();
+    assert_eq!(failed, vec![1, 2, 3, 4], "{:?}", report);
+
+    assert_eq!(parse_iso8601_datetime("2021-03-14T07:30Z"), None);
+    assert!(parse_iso8601_offset_datetime("2021-03-14T07:30").is_none());
+}
+
+#[test]
+fn round_trips_in_extended_form() {
+    let records = Observation::from_csv_reader(INPUT.as_bytes()).unwrap();
+    let written = to_csv(&records);
+    assert_eq!(
+        written,
+        "date,taken,recorded,onset\n\
+         2021-03-14,2021-03-14T07:30:00,2021-03-14T12:30:00Z,2015\n\
+         2021-03-14,2021-03-14T07:30:00.500,2021-03-14T12:30:00Z,2015-03\n\
+         2021-03-14,,2021-03-14T12:30:00Z,\n"
+    );
+
+    let reread = Observation::from_csv_reader(written.as_bytes()).unwrap();
+    for (reread, record) in reread.iter().zip(&records) {
+        assert_eq!(reread.date, record.date);
+        assert_eq!(reread.taken, record.taken);
+        assert_eq!(reread.recorded, record.recorded);
+        assert_eq!(reread.onset, record.onset);
+    }
+}
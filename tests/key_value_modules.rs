@@ -0,0 +1,79 @@
+mod common;
+
+use common::to_csv;
+use deserialize::{flexible_bool, key_value, key_value_opt, split_pairs, FromCsv, LoadOptions};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, Deserialize, Serialize)]
+struct Anesthesia {
+    asa: u8,
+    #[serde(with = "flexible_bool::y_n")]
+    emergency: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Case {
+    #[serde(with = "key_value")]
+    tags: BTreeMap<String, String>,
+    #[serde(with = "key_value_opt")]
+    anesthesia: Option<Anesthesia>,
+}
+
+impl FromCsv for Case {}
+
+#[test]
+fn reads_pairs_into_maps_and_structs() {
+    let input = "tags,anesthesia\n\
+                 \"site = OR 3; \"\"note=a;b\"\"\",asa=3;emergency=yes\n\
+                 \"\",\"\"\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Case::from_csv_reader(input.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+
+    let tags: Vec<_> = records[0]
+        .tags
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.as_str()))
+        .collect();
+    assert_eq!(tags, vec![("note", "a;b"), ("site", "OR 3")]);
+    assert_eq!(
+        records[0].anesthesia,
+        Some(Anesthesia {
+            asa: 3,
+            emergency: true
+        })
+    );
+    assert!(records[1].tags.is_empty());
+    assert_eq!(records[1].anesthesia, None);
+}
+
+#[test]
+fn rejects_values_without_keys() {
+    assert!(split_pairs("asa=3;emergency", ';').is_err());
+
+    let input = "tags,anesthesia\n\
+                 site,\"\"\n\
+                 site=OR,asa=three;emergency=N\n\
+                 site=OR,asa=3\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Case::from_csv_reader(input.as_bytes()));
+    assert!(records.unwrap().is_empty());
+    let failed: Vec<_> = report.errors.iter().map(|error| error.row).collect();
+    assert_eq!(failed, vec![1, 2, 3], "{:?}", report);
+}
+
+#[test]
+fn round_trips_through_csv() {
+    let input = "tags,anesthesia\n\"site=OR 3;\"\"note=a;b\"\"\",asa=3;emergency=N\n";
+    let records = Case::from_csv_reader(input.as_bytes()).unwrap();
+    let written = to_csv(&records);
+    assert_eq!(
+        written,
+        "tags,anesthesia\n\"\"\"note=a;b\"\";site=OR 3\",asa=3;emergency=N\n"
+    );
+    let reread = Case::from_csv_reader(written.as_bytes()).unwrap();
+    assert_eq!(reread[0].tags, records[0].tags);
+    assert_eq!(reread[0].anesthesia, records[0].anesthesia);
+}
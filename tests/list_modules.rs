@@ -0,0 +1,142 @@
+mod common;
+
+use common::to_csv;
+use deserialize::{
+    comma_separated, delimited, delimited_opt, delimited_set, semi_separated_list,
+    unique_delimited, FromCsv, LoadOptions, Semicolon,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashSet};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Order {
+    #[serde(with = "delimited")]
+    skus: Vec<String>,
+    #[serde(
+        deserialize_with = "delimited_opt::deserialize_as::<Semicolon, _, _>",
+        serialize_with = "delimited_opt::serialize_as::<Semicolon, _, _>"
+    )]
+    doses: Option<Vec<String>>,
+}
+
+impl FromCsv for Order {}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct LegacyOrder {
+    #[serde(with = "comma_separated")]
+    skus: Vec<String>,
+    #[serde(with = "semi_separated_list")]
+    doses: Vec<String>,
+}
+
+impl FromCsv for LegacyOrder {}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Survey {
+    #[serde(with = "delimited_set")]
+    rooms: BTreeSet<u32>,
+    #[serde(
+        deserialize_with = "delimited_set::deserialize_ignore_case",
+        serialize_with = "delimited_set::serialize"
+    )]
+    allergies: HashSet<String>,
+    #[serde(with = "unique_delimited")]
+    visits: Vec<u32>,
+    #[serde(
+        deserialize_with = "unique_delimited::deserialize_ignore_case",
+        serialize_with = "unique_delimited::serialize"
+    )]
+    surgeons: Vec<String>,
+}
+
+impl FromCsv for Survey {}
+
+#[test]
+fn splits_lists_as_written() {
+    let input = "skus,doses\n\
+                 00123,1.50\n\
+                 \"00123, 1.50 ,,1e3\",\" 0.50; \"\"1;2\"\" \"\n\
+                 \"\",\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Order::from_csv_reader(input.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+
+    assert_eq!(records[0].skus, vec!["00123"]);
+    assert_eq!(records[0].doses, Some(vec!["1.50".to_string()]));
+    assert_eq!(records[1].skus, vec!["00123", "1.50", "1e3"]);
+    assert_eq!(
+        records[1].doses,
+        Some(vec!["0.50".to_string(), "1;2".to_string()])
+    );
+    assert!(records[2].skus.is_empty());
+    assert_eq!(records[2].doses, None);
+
+    assert_eq!(
+        to_csv(&records),
+        "skus,doses\n00123,1.50\n\"00123,1.50,1e3\",\"0.50;\"\"1;2\"\"\"\n,\n"
+    );
+}
+
+#[test]
+fn keeps_legacy_list_splitting() {
+    let input = "skus,doses\n\
+                 \"00123, 1.50 ,,1e3\",\"0.50; 1;\"\n\
+                 \"\",\"\"\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| LegacyOrder::from_csv_reader(input.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+
+    assert_eq!(records[0].skus, vec!["00123", "1.50", "", "1e3"]);
+    assert_eq!(records[0].doses, vec!["0.50", " 1", ""]);
+    assert!(records[1].skus.is_empty());
+    assert_eq!(records[1].doses, vec![""]);
+
+    assert_eq!(
+        to_csv(&records),
+        "skus,doses\n\"00123,1.50,,1e3\",0.50; 1;\n,\n"
+    );
+}
+
+#[test]
+fn reads_sets_and_unique_lists() {
+    let input = "rooms,allergies,visits,surgeons\n\
+                 \"10,2,10\",\"Latex, LATEX, Penicillin\",\"3,1,3\",\"Smith, Jones, SMITH\"\n\
+                 \"\",\"\",\"\",\"\"\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Survey::from_csv_reader(input.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+
+    assert_eq!(records[0].rooms, [2, 10].iter().copied().collect());
+    let allergies: HashSet<String> = ["Latex", "Penicillin"]
+        .iter()
+        .map(|allergy| allergy.to_string())
+        .collect();
+    assert_eq!(records[0].allergies, allergies);
+    assert_eq!(records[0].visits, vec![3, 1]);
+    assert_eq!(records[0].surgeons, vec!["Smith", "Jones"]);
+    assert!(records[1].rooms.is_empty());
+    assert!(records[1].surgeons.is_empty());
+
+    // Sets are written in element order, so 2 comes before 10
+    assert_eq!(
+        to_csv(&records),
+        "rooms,allergies,visits,surgeons\n\
+         \"2,10\",\"Latex,Penicillin\",\"3,1\",\"Smith,Jones\"\n\
+         ,,,\n"
+    );
+}
+
+#[test]
+fn rejects_set_elements_that_do_not_parse() {
+    let input = "rooms,allergies,visits,surgeons\n\
+                 \"2,ICU\",Latex,1,Smith\n\
+                 2,Latex,\"1,x\",Smith\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Survey::from_csv_reader(input.as_bytes()));
+    assert!(records.unwrap().is_empty());
+    let failed: Vec<_> = report.errors.iter().map(|error| error.row).collect();
+    assert_eq!(failed, vec![1, 2], "{:?}", report);
+}
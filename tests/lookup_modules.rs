@@ -0,0 +1,83 @@
+mod common;
+
+use common::to_csv;
+use deserialize::{
+    lookup, lookup_opt, FromCsv, LoadOptions, LookupEntry, LookupMiss, LookupSource, LookupTable,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize)]
+struct DepartmentRow {
+    id: String,
+    name: String,
+}
+
+impl FromCsv for DepartmentRow {}
+
+impl LookupEntry for DepartmentRow {
+    fn code(&self) -> &str {
+        &self.id
+    }
+
+    fn value(&self) -> &str {
+        &self.name
+    }
+}
+
+struct Departments;
+
+impl LookupSource for Departments {
+    const TABLE: &'static str = "departments";
+}
+
+struct OptionalDepartments;
+
+impl LookupSource for OptionalDepartments {
+    const TABLE: &'static str = "departments";
+    const ON_MISS: LookupMiss = LookupMiss::Null;
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Case {
+    #[serde(
+        deserialize_with = "lookup::deserialize_as::<Departments, _, _>",
+        serialize_with = "lookup::serialize"
+    )]
+    department: String,
+    #[serde(
+        deserialize_with = "lookup_opt::deserialize_as::<OptionalDepartments, _, _>",
+        serialize_with = "lookup_opt::serialize"
+    )]
+    referred_to: Option<String>,
+}
+
+impl FromCsv for Case {}
+
+#[test]
+fn looks_up_codes_as_written() {
+    let departments = "id,name\n0042,Surgery\n42,Radiology\n1.50,Pharmacy\n";
+    let table = LookupTable::from_csv_reader::<DepartmentRow, _>(departments.as_bytes()).unwrap();
+
+    let input = "department,referred_to\n0042,42\n42,1.5\n1.50,\n1.5,0042\n";
+    let (records, report) = LoadOptions::new()
+        .lookup_table("departments", table)
+        .run_with_report(|| Case::from_csv_reader(input.as_bytes()));
+    let records = records.unwrap();
+
+    // "1.5" is not the code "1.50"
+    assert_eq!(report.loaded, 3, "{:?}", report);
+    assert_eq!(report.errors.len(), 1, "{:?}", report);
+    assert_eq!(report.errors[0].row, 4);
+
+    assert_eq!(records[0].department, "Surgery");
+    assert_eq!(records[0].referred_to.as_deref(), Some("Radiology"));
+    assert_eq!(records[1].department, "Radiology");
+    assert_eq!(records[1].referred_to, None);
+    assert_eq!(records[2].department, "Pharmacy");
+    assert_eq!(records[2].referred_to, None);
+
+    assert_eq!(
+        to_csv(&records),
+        "department,referred_to\nSurgery,Radiology\nRadiology,\nPharmacy,\n"
+    );
+}
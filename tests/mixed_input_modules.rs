@@ -0,0 +1,92 @@
+mod common;
+
+use common::to_csv;
+use deserialize::{
+    possibly_empty_parseable_value, with_text_cells, with_typed_cells, zero_one_bool,
+    zero_one_int_bool, FromCsv, LoadOptions,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Parsed {
+    #[serde(deserialize_with = "possibly_empty_parseable_value::deserialize")]
+    text: Option<String>,
+    #[serde(deserialize_with = "possibly_empty_parseable_value::deserialize")]
+    number: Option<f64>,
+}
+
+impl FromCsv for Parsed {}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Flags {
+    #[serde(with = "zero_one_bool")]
+    flag: bool,
+    #[serde(with = "zero_one_int_bool")]
+    int_flag: bool,
+}
+
+impl FromCsv for Flags {}
+
+#[test]
+fn parseable_value_keeps_cell_text() {
+    let input = "text,number\n00123,1.50\n1.50,00123\n1e3,\n,NULL\n";
+    let records = Parsed::from_csv_reader(input.as_bytes()).unwrap();
+    let texts: Vec<_> = records
+        .iter()
+        .map(|record| record.text.as_deref())
+        .collect();
+    assert_eq!(texts, vec![Some("00123"), Some("1.50"), Some("1e3"), None]);
+    let numbers: Vec<_> = records.iter().map(|record| record.number).collect();
+    assert_eq!(numbers, vec![Some(1.5), Some(123.0), None, None]);
+    assert_eq!(
+        to_csv(&records),
+        "text,number\n00123,1.5\n1.50,123.0\n1e3,\n,\n"
+    );
+}
+
+#[test]
+fn parseable_value_reads_plain_csv_readers_as_text() {
+    let mut reader = csv::Reader::from_reader("text,number\n00123,1.50\n".as_bytes());
+    let record: Parsed = with_text_cells(|| reader.deserialize().next().unwrap()).unwrap();
+    assert_eq!(record.text.as_deref(), Some("00123"));
+}
+
+#[test]
+fn bools_reject_reformatted_numbers() {
+    let input = "flag,int_flag\n1,1\n0,0\n00,0\n1.0,1\ntrue,1\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Flags::from_csv_reader(input.as_bytes()));
+    let flags: Vec<_> = records.unwrap().iter().map(|record| record.flag).collect();
+    assert_eq!(flags, vec![true, false, true]);
+    let failed: Vec<_> = report.errors.iter().map(|error| error.row).collect();
+    assert_eq!(failed, vec![3, 4]);
+}
+
+#[test]
+fn bools_round_trip() {
+    let input = "flag,int_flag\n1,1\n0,0\n";
+    let records = Flags::from_csv_reader(input.as_bytes()).unwrap();
+    assert_eq!(to_csv(&records), input);
+}
+
+#[test]
+fn typed_cells_accept_numbers_and_bools() {
+    let json = r#"[{"flag": 1, "int_flag": 0}, {"flag": true, "int_flag": 1.0}]"#;
+    let records: Vec<Flags> = serde_json::from_str(json).unwrap();
+    let flags: Vec<_> = records
+        .iter()
+        .map(|record| (record.flag, record.int_flag))
+        .collect();
+    assert_eq!(flags, vec![(true, false), (true, true)]);
+
+    let parsed: Parsed = serde_json::from_str(r#"{"text": 123, "number": 1.5}"#).unwrap();
+    assert_eq!(parsed.text.as_deref(), Some("123"));
+    assert_eq!(parsed.number, Some(1.5));
+
+    // A JSON document read inside a CSV load still has typed cells
+    let flags: Flags = with_text_cells(|| {
+        with_typed_cells(|| serde_json::from_str(r#"{"flag": 1, "int_flag": 0}"#))
+    })
+    .unwrap();
+    assert!(flags.flag && !flags.int_flag);
+}
@@ -0,0 +1,87 @@
+mod common;
+
+use common::to_csv;
+use deserialize::{
+    is_null_token, null_tokens, nullable_field, reset_null_tokens, set_null_tokens, FromCsv,
+    LoadOptions,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Reading {
+    #[serde(default, deserialize_with = "nullable_field::deserialize_strict")]
+    count: Option<i32>,
+    #[serde(default, deserialize_with = "nullable_field::deserialize_strict")]
+    note: Option<String>,
+}
+
+impl FromCsv for Reading {}
+
+fn failed_rows(input: &str, options: LoadOptions) -> (Vec<Reading>, Vec<usize>) {
+    let (records, report) = options.run_with_report(|| Reading::from_csv_reader(input.as_bytes()));
+    let failed = report.errors.iter().map(|error| error.row).collect();
+    (records.unwrap(), failed)
+}
+
+// The tokens set by `set_null_tokens` are shared by every test in this file, so
+// only this test changes them
+#[test]
+fn set_null_tokens_replaces_the_defaults() {
+    let input = "count,note\nNA,NA\n-,-\n";
+
+    let (records, failed) = failed_rows(input, LoadOptions::new());
+    assert_eq!(failed, vec![2]);
+    assert_eq!(records[0].count, None);
+    assert_eq!(records[0].note, None);
+
+    set_null_tokens(vec![" - ", "n/a"]);
+    assert_eq!(null_tokens(), vec!["-", "n/a"]);
+    assert!(is_null_token(" n/a "));
+    assert!(!is_null_token("N/A"));
+    let (records, failed) = failed_rows(input, LoadOptions::new());
+    assert_eq!(failed, vec![1]);
+    assert_eq!(records[0].count, None);
+    assert_eq!(records[0].note, None);
+
+    // A load's own tokens win over the shared ones
+    let (_, failed) = failed_rows(input, LoadOptions::new().null_tokens(vec!["NA"]));
+    assert_eq!(failed, vec![2]);
+
+    reset_null_tokens();
+    assert_eq!(null_tokens(), vec!["", "NULL", "NA"]);
+    assert!(!is_null_token("n/a"));
+}
+
+#[test]
+fn load_tokens_apply_only_within_the_load() {
+    let input = "count,note\n?,?\n12,\"\"\n";
+    let options = LoadOptions::new().null_tokens(vec!["?", ""]);
+    let (records, failed) = failed_rows(input, options);
+    assert!(failed.is_empty());
+    assert_eq!(records[0].count, None);
+    assert_eq!(records[0].note, None);
+    assert_eq!(records[1].count, Some(12));
+    assert_eq!(records[1].note, None);
+
+    // Without "" among the tokens an empty cell is an empty string
+    let (records, failed) = failed_rows(input, LoadOptions::new().null_tokens(vec!["NULL"]));
+    assert_eq!(failed, vec![1]);
+    assert_eq!(records[0].note.as_deref(), Some(""));
+}
+
+#[test]
+fn nulls_read_back_after_writing() {
+    let input = "count,note\n?,open\n12,?\n";
+    let options = LoadOptions::new().null_tokens(vec!["?", ""]);
+    let (records, failed) = failed_rows(input, options.clone());
+    assert!(failed.is_empty());
+
+    let written = to_csv(&records);
+    assert_eq!(written, "count,note\n,open\n12,\n");
+    let (reread, failed) = failed_rows(&written, options);
+    assert!(failed.is_empty());
+    assert_eq!(reread[0].count, None);
+    assert_eq!(reread[0].note.as_deref(), Some("open"));
+    assert_eq!(reread[1].count, Some(12));
+    assert_eq!(reread[1].note, None);
+}
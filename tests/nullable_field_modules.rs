@@ -0,0 +1,129 @@
+use deserialize::{enum_from_id_opt, nullable_field, FromCsv, LoadOptions};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct Strict {
+    #[serde(default, deserialize_with = "nullable_field::deserialize_strict")]
+    code: Option<String>,
+    #[serde(default, deserialize_with = "nullable_field::deserialize_strict")]
+    count: Option<i32>,
+    #[serde(default, deserialize_with = "nullable_field::deserialize_strict")]
+    amount: Option<f64>,
+}
+
+impl FromCsv for Strict {}
+
+#[derive(Debug, Deserialize)]
+struct Audited {
+    #[serde(default, deserialize_with = "nullable_field::deserialize_audited")]
+    count: Option<i32>,
+}
+
+impl FromCsv for Audited {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Open,
+    Closed,
+}
+
+impl std::convert::TryFrom<i32> for Status {
+    type Error = String;
+
+    fn try_from(id: i32) -> Result<Self, Self::Error> {
+        match id {
+            1 => Ok(Status::Open),
+            2 => Ok(Status::Closed),
+            _ => Err(format!("unknown status {}", id)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Statuses {
+    #[serde(deserialize_with = "enum_from_id_opt::deserialize_strict")]
+    status: Option<Status>,
+}
+
+impl FromCsv for Statuses {}
+
+#[derive(Debug, Deserialize)]
+struct Nested {
+    #[serde(default, deserialize_with = "nullable_field::deserialize_strict")]
+    values: Option<Vec<u32>>,
+}
+
+#[test]
+fn strict_keeps_cell_text() {
+    let input = "code,count,amount\n007,0012,1.50\nNULL,,NA\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Strict::from_csv_reader(input.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+    assert_eq!(records[0].code.as_deref(), Some("007"));
+    assert_eq!(records[0].count, Some(12));
+    assert_eq!(records[0].amount, Some(1.5));
+    assert_eq!(records[1].code, None);
+    assert_eq!(records[1].count, None);
+    assert_eq!(records[1].amount, None);
+}
+
+#[test]
+fn strict_propagates_parse_errors() {
+    let input = "code,count,amount\nA,12,1.5\nB,1x,1.5\nC,3,one\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Strict::from_csv_reader(input.as_bytes()));
+    assert_eq!(records.unwrap().len(), 1);
+    let failed: Vec<_> = report.errors.iter().map(|error| error.row).collect();
+    assert_eq!(failed, vec![2, 3]);
+}
+
+#[test]
+fn audited_records_warnings() {
+    let input = "count\n5\n5x\nNULL\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Audited::from_csv_reader(input.as_bytes()));
+    let counts: Vec<_> = records.unwrap().iter().map(|record| record.count).collect();
+    assert_eq!(counts, vec![Some(5), None, None]);
+    assert_eq!(report.warnings.len(), 1);
+    assert_eq!(report.warnings[0].row, 2);
+    assert!(report.warnings[0].message.contains("\"5x\""));
+}
+
+#[test]
+fn strict_reads_any_deserializable_type() {
+    let json = r#"[{"values": [1, 2, 3]}, {"values": null}, {"values": "NULL"}]"#;
+    let records: Vec<Nested> = serde_json::from_str(json).unwrap();
+    let values: Vec<_> = records.into_iter().map(|record| record.values).collect();
+    assert_eq!(values, vec![Some(vec![1, 2, 3]), None, None]);
+    assert!(serde_json::from_str::<Nested>(r#"{"values": [1, "x"]}"#).is_err());
+}
+
+#[test]
+fn enum_from_id_opt_strict_reports_unknown_ids() {
+    let input = "status\n1\n02\n\"\"\n7\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Statuses::from_csv_reader(input.as_bytes()));
+    let statuses: Vec<_> = records
+        .unwrap()
+        .iter()
+        .map(|record| record.status)
+        .collect();
+    assert_eq!(
+        statuses,
+        vec![Some(Status::Open), Some(Status::Closed), None]
+    );
+    assert_eq!(report.errors.len(), 1);
+    assert_eq!(report.errors[0].row, 4);
+}
+
+#[test]
+fn rows_restart_for_each_load_in_a_report() {
+    let (_, report) = LoadOptions::new().run_with_report(|| {
+        Audited::from_csv_reader("count\n1\nx\n".as_bytes()).unwrap();
+        Audited::from_csv_reader("count\ny\n2\n".as_bytes()).unwrap();
+    });
+    let warned: Vec<_> = report.warnings.iter().map(|warning| warning.row).collect();
+    assert_eq!(warned, vec![2, 1]);
+    assert_eq!(report.loaded, 4);
+}
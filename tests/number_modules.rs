@@ -0,0 +1,70 @@
+mod common;
+
+use common::to_csv;
+use deserialize::{number, number_opt, with_text_cells, EuropeanLocale, FromCsv, LoadOptions};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct European {
+    #[serde(
+        deserialize_with = "number::deserialize_as::<EuropeanLocale, _, _>",
+        serialize_with = "number::serialize_as::<EuropeanLocale, _, _>"
+    )]
+    count: i64,
+    #[serde(
+        deserialize_with = "number_opt::deserialize_as::<EuropeanLocale, _, _>",
+        serialize_with = "number_opt::serialize_as::<EuropeanLocale, _, _>"
+    )]
+    weight: Option<f64>,
+}
+
+impl FromCsv for European {}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Us {
+    #[serde(with = "number")]
+    count: u32,
+    #[serde(with = "number_opt")]
+    weight: Option<f64>,
+}
+
+impl FromCsv for Us {}
+
+#[test]
+fn european_locale_applies_to_plain_numeric_cells() {
+    let input = "count;weight\n1.234;1.234\n00123;1,50\n\"1.234.567\";\"12,5 %\"\n7;\n";
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(b';')
+        .from_reader(input.as_bytes());
+    let records: Vec<European> =
+        with_text_cells(|| reader.deserialize().map(Result::unwrap).collect());
+    let counts: Vec<_> = records.iter().map(|record| record.count).collect();
+    assert_eq!(counts, vec![1234, 123, 1234567, 7]);
+    let weights: Vec<_> = records.iter().map(|record| record.weight).collect();
+    assert_eq!(weights, vec![Some(1234.0), Some(1.5), Some(0.125), None]);
+    assert_eq!(
+        to_csv(&records),
+        "count,weight\n1.234,1.234\n123,\"1,5\"\n1.234.567,\"0,125\"\n7,\n"
+    );
+}
+
+#[test]
+fn us_locale_round_trips() {
+    let input = "count,weight\n\"1,234\",1.50\n00123,\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Us::from_csv_reader(input.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+    assert_eq!(records[0].count, 1234);
+    assert_eq!(records[1].count, 123);
+    assert_eq!(to_csv(&records), "count,weight\n1234,1.5\n123,\n");
+}
+
+#[test]
+fn rejects_non_numeric_text() {
+    let input = "count,weight\n1x,1\n12,abc\n-3,1\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Us::from_csv_reader(input.as_bytes()));
+    assert!(records.unwrap().is_empty());
+    assert_eq!(report.errors.len(), 3);
+}
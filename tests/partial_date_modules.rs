@@ -0,0 +1,125 @@
+mod common;
+
+use common::{csv_of, to_csv, ymd};
+use deserialize::{
+    partial_date, partial_date_opt, DatePrecision, FromCsv, LoadOptions, PartialDate,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct History {
+    #[serde(with = "partial_date::us")]
+    onset: PartialDate,
+}
+
+impl FromCsv for History {}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct OptionalHistory {
+    #[serde(with = "partial_date_opt")]
+    onset: Option<PartialDate>,
+}
+
+impl FromCsv for OptionalHistory {}
+
+#[test]
+fn reads_and_round_trips_partial_dates() {
+    let input = csv_of(
+        "onset",
+        &[
+            "2015",
+            "03/2015",
+            "UNK/2015",
+            "March 2015",
+            "3/14/2015",
+            "2015-03-14",
+        ],
+    );
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| History::from_csv_reader(input.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+    let precisions: Vec<_> = records
+        .iter()
+        .map(|record| record.onset.precision())
+        .collect();
+    assert_eq!(
+        precisions,
+        vec![
+            DatePrecision::Year,
+            DatePrecision::Month,
+            DatePrecision::Year,
+            DatePrecision::Month,
+            DatePrecision::Day,
+            DatePrecision::Day,
+        ]
+    );
+    assert_eq!(
+        to_csv(&records),
+        "onset\n2015\n03/2015\n2015\n03/2015\n03/14/2015\n03/14/2015\n"
+    );
+}
+
+#[test]
+fn rejects_numeric_text_that_is_not_a_year() {
+    let input = csv_of(
+        "onset",
+        &["1842.0", "00123", "Août 2015", "400000", "2015.5"],
+    );
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| History::from_csv_reader(input.as_bytes()));
+    assert!(records.unwrap().is_empty());
+    assert_eq!(report.errors.len(), 5);
+}
+
+#[test]
+fn unknown_dates_are_none() {
+    let input = csv_of("onset", &["", "UNKNOWN", "?", "2015"]);
+    let records = OptionalHistory::from_csv_reader(input.as_bytes()).unwrap();
+    let onsets: Vec<_> = records.iter().map(|record| record.onset).collect();
+    assert_eq!(
+        onsets,
+        vec![None, None, None, PartialDate::new(2015, None, None)]
+    );
+}
+
+#[test]
+fn whole_typed_floats_are_years_and_others_excel_serials() {
+    let json = r#"[{"onset": 2015.0}, {"onset": 2015}, {"onset": 42078.0}, {"onset": 2015.5}]"#;
+    let records: Vec<History> = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        records[0].onset,
+        PartialDate::new(2015, None, None).unwrap()
+    );
+    assert_eq!(
+        records[1].onset,
+        PartialDate::new(2015, None, None).unwrap()
+    );
+    assert_eq!(records[2].onset, PartialDate::from(ymd(2015, 3, 15)));
+    assert_eq!(records[3].onset, PartialDate::from(ymd(1905, 7, 7)));
+}
+
+#[test]
+fn month_names_must_be_whole_or_abbreviated() {
+    let may = PartialDate::new(2015, Some(5), None);
+    assert_eq!(PartialDate::parse("May 2015"), may);
+    assert_eq!(
+        PartialDate::parse("Sep. 2015"),
+        PartialDate::new(2015, Some(9), None)
+    );
+    assert_eq!(
+        PartialDate::parse("september 2015"),
+        PartialDate::new(2015, Some(9), None)
+    );
+    assert!(PartialDate::parse("Mayhem 2015").is_none());
+    assert!(PartialDate::parse("Septem 2015").is_none());
+}
+
+#[test]
+fn new_rejects_years_chrono_cannot_represent() {
+    assert!(PartialDate::new(400000, None, None).is_none());
+    assert!(PartialDate::new(400000, Some(3), None).is_none());
+    assert!(PartialDate::parse("Août 2015").is_none());
+    let year = PartialDate::new(2015, None, None).unwrap();
+    assert_eq!(year.range(), ymd(2015, 1, 1)..=ymd(2015, 12, 31));
+}
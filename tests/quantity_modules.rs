@@ -0,0 +1,96 @@
+mod common;
+
+use common::to_csv;
+use deserialize::{
+    measurement, measurement_opt, units, Dimension, DoseRate, FromCsv, Height, LoadOptions,
+    Quantity, Unit, Weight,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Vitals {
+    #[serde(
+        deserialize_with = "measurement::deserialize_as::<Weight, _>",
+        serialize_with = "measurement::serialize_in::<units::Kg, _>"
+    )]
+    weight: Quantity,
+    #[serde(
+        deserialize_with = "measurement_opt::deserialize_as::<Height, _>",
+        serialize_with = "measurement_opt::serialize_in::<units::Cm, _>"
+    )]
+    height: Option<Quantity>,
+    #[serde(
+        deserialize_with = "measurement_opt::deserialize_as::<DoseRate, _>",
+        serialize_with = "measurement_opt::serialize"
+    )]
+    infusion: Option<Quantity>,
+}
+
+impl FromCsv for Vitals {}
+
+#[test]
+fn parses_quantities_in_their_units() {
+    let input = "weight,height,infusion\n\
+                 72,180,0.5\n\
+                 158 lb,\"5'10\"\"\",50 mcg/kg/min\n\
+                 1.5e3 g,1.8 m,\"\"\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Vitals::from_csv_reader(input.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+
+    assert_eq!(records[0].weight, Quantity::new(72.0, Unit::Kilogram));
+    assert_eq!(
+        records[0].infusion,
+        Some(Quantity::new(0.5, Unit::MicrogramPerKilogramPerMinute))
+    );
+    assert_eq!(records[1].weight, Quantity::new(158.0, Unit::Pound));
+    assert_eq!(records[1].height.unwrap().unit(), Unit::Foot);
+    let inches = records[1].height.unwrap().value_in(Unit::Inch).unwrap();
+    assert!((inches - 70.0).abs() < 1e-9);
+    assert_eq!(records[2].weight, Quantity::new(1500.0, Unit::Gram));
+    assert_eq!(records[2].weight.value_in(Unit::Kilogram), Some(1.5));
+    assert_eq!(records[2].infusion, None);
+}
+
+#[test]
+fn rejects_unknown_units_and_other_dimensions() {
+    let input = "weight,height,infusion\n\
+                 72 stone,180,\"\"\n\
+                 72 cm,180,\"\"\n\
+                 72,180 kg,\"\"\n\
+                 72,180,5 mg/h\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Vitals::from_csv_reader(input.as_bytes()));
+    assert!(records.unwrap().is_empty());
+    let failed: Vec<_> = report.errors.iter().map(|error| error.row).collect();
+    assert_eq!(failed, vec![1, 2, 3, 4], "{:?}", report);
+
+    assert!(Quantity::parse("1.5e mg", None, None).is_err());
+    assert!(Quantity::parse("5", Some(Dimension::Mass), None).is_err());
+}
+
+#[test]
+fn converts_and_round_trips() {
+    let dose: Quantity = "1.5e3 mg".parse().unwrap();
+    assert_eq!(dose.value(), 1500.0);
+    assert_eq!(dose.to_string(), "1500 mg");
+    assert_eq!(dose.format_in(Unit::Gram), Some("1.5 g".to_string()));
+    assert_eq!(dose.format_in(Unit::Meter), None);
+    assert_eq!(
+        Quantity::new(0.12345678, Unit::Milligram).to_string(),
+        "0.12345678 mg"
+    );
+
+    let input = "weight,height,infusion\n72.25,180.5,0.125\n";
+    let records = Vitals::from_csv_reader(input.as_bytes()).unwrap();
+    let written = to_csv(&records);
+    assert_eq!(
+        written,
+        "weight,height,infusion\n72.25 kg,180.5 cm,0.125 mcg/kg/min\n"
+    );
+    let reread = Vitals::from_csv_reader(written.as_bytes()).unwrap();
+    assert_eq!(reread[0].weight, records[0].weight);
+    assert_eq!(reread[0].height, records[0].height);
+    assert_eq!(reread[0].infusion, records[0].infusion);
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use common::to_csv;
+use deserialize::{
+    normalize_text, normalized_string, normalized_string_opt, trim_text, trimmed_string,
+    trimmed_string_opt, FromCsv, LetterCase, LoadOptions, UpperCase,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Contact {
+    #[serde(with = "trimmed_string")]
+    name: String,
+    #[serde(with = "trimmed_string_opt")]
+    nickname: Option<String>,
+    #[serde(
+        deserialize_with = "normalized_string::deserialize_as::<UpperCase, _>",
+        serialize_with = "normalized_string::serialize"
+    )]
+    city: String,
+    #[serde(with = "normalized_string_opt")]
+    note: Option<String>,
+}
+
+impl FromCsv for Contact {}
+
+const INPUT: &str = "name,nickname,city,note\n\
+                     \"\u{200B} José \",\"\u{00A0}\",\" saint\u{00A0}\u{00A0}paul \",\"Jose\u{301}  was\n here\"\n\
+                     Lee,\" NULL \",Duluth,\"\u{200D}\"\n";
+
+#[test]
+fn trims_and_normalizes_text() {
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Contact::from_csv_reader(INPUT.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+
+    assert_eq!(records[0].name, "José");
+    assert_eq!(records[0].nickname, None);
+    assert_eq!(records[0].city, "SAINT PAUL");
+    // NFC composes "e" and the combining accent into a single "é"
+    assert_eq!(records[0].note.as_deref(), Some("Jos\u{e9} was here"));
+    assert_eq!(records[1].nickname, None);
+    assert_eq!(records[1].city, "DULUTH");
+    assert_eq!(records[1].note, None);
+
+    assert_eq!(trim_text("\u{FEFF} a  b \u{2060}"), "a  b");
+    assert_eq!(normalize_text(" A\tB ", LetterCase::Lower), "a b");
+}
+
+#[test]
+fn rejects_cells_that_are_not_text() {
+    let err = serde_json::from_str::<Contact>(
+        r#"{"name": 5, "nickname": "", "city": "Duluth", "note": ""}"#,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("invalid type"), "{}", err);
+}
+
+#[test]
+fn round_trips_cleaned_text() {
+    let records = Contact::from_csv_reader(INPUT.as_bytes()).unwrap();
+    let written = to_csv(&records);
+    assert_eq!(
+        written,
+        "name,nickname,city,note\nJosé,,SAINT PAUL,José was here\nLee,,DULUTH,\n"
+    );
+    let reread = Contact::from_csv_reader(written.as_bytes()).unwrap();
+    for (reread, record) in reread.iter().zip(&records) {
+        assert_eq!(reread.name, record.name);
+        assert_eq!(reread.nickname, record.nickname);
+        assert_eq!(reread.city, record.city);
+        assert_eq!(reread.note, record.note);
+    }
+}
@@ -0,0 +1,116 @@
+mod common;
+
+use chrono::NaiveDate;
+use common::{to_csv, ymd};
+use deserialize::{
+    adjust_century, mm_dd_yy_birthdate, mm_dd_yy_birthdate_opt, mm_dd_yy_date, parse_birthdate,
+    parse_mm_dd_yy, FixedPivot, FromCsv, LoadOptions, YearPivot,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Admission {
+    #[serde(with = "mm_dd_yy_date")]
+    admitted: NaiveDate,
+    #[serde(
+        deserialize_with = "mm_dd_yy_date::deserialize_as::<FixedPivot<1930>, _>",
+        serialize_with = "mm_dd_yy_date::serialize"
+    )]
+    scheduled: NaiveDate,
+}
+
+impl FromCsv for Admission {}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Patient {
+    #[serde(with = "mm_dd_yy_birthdate")]
+    born: NaiveDate,
+    #[serde(with = "mm_dd_yy_birthdate_opt")]
+    mother_born: Option<NaiveDate>,
+}
+
+impl FromCsv for Patient {}
+
+const ADMISSIONS: &str = "admitted,scheduled\n\
+                          03/14/69,03/14/29\n\
+                          03/14/70,03/14/30\n\
+                          03/14/2069,03/14/1929\n";
+
+#[test]
+fn reads_two_digit_years_in_their_window() {
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Admission::from_csv_reader(ADMISSIONS.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+    // chrono's own window starts at 1970
+    assert_eq!(records[0].admitted, ymd(2069, 3, 14));
+    assert_eq!(records[1].admitted, ymd(1970, 3, 14));
+    assert_eq!(records[2].admitted, ymd(2069, 3, 14));
+    assert_eq!(records[0].scheduled, ymd(2029, 3, 14));
+    assert_eq!(records[1].scheduled, ymd(1930, 3, 14));
+    assert_eq!(records[2].scheduled, ymd(1929, 3, 14));
+
+    // A load's pivot applies to `deserialize`, but not to a field's own window
+    let (records, report) = LoadOptions::new()
+        .year_pivot(YearPivot::Fixed(1950))
+        .run_with_report(|| Admission::from_csv_reader(ADMISSIONS.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+    assert_eq!(records[0].admitted, ymd(1969, 3, 14));
+    assert_eq!(records[0].scheduled, ymd(2029, 3, 14));
+
+    let window = YearPivot::SlidingWindow { years_ahead: 10 };
+    assert_eq!(window.resolve_relative_to(31, ymd(2021, 6, 1)), 2031);
+    assert_eq!(window.resolve_relative_to(32, ymd(2021, 6, 1)), 1932);
+    assert_eq!(
+        adjust_century(ymd(2069, 3, 14), ymd(2021, 3, 14), 0),
+        Some(ymd(1969, 3, 14))
+    );
+}
+
+#[test]
+fn reads_birthdates_in_the_past() {
+    let input = "born,mother_born\n03/14/85,12/31/99\n12/31/1899,NULL\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Patient::from_csv_reader(input.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+    assert_eq!(records[0].born, ymd(1985, 3, 14));
+    assert_eq!(records[0].mother_born, Some(ymd(1999, 12, 31)));
+    assert_eq!(records[1].born, ymd(1899, 12, 31));
+    assert_eq!(records[1].mother_born, None);
+}
+
+#[test]
+fn rejects_malformed_and_future_dates() {
+    assert_eq!(parse_mm_dd_yy("03/14/1", YearPivot::default()), None);
+    assert_eq!(parse_mm_dd_yy("03/14/021", YearPivot::default()), None);
+    assert_eq!(parse_mm_dd_yy("13/14/21", YearPivot::default()), None);
+    assert_eq!(parse_mm_dd_yy("2021-03-14", YearPivot::default()), None);
+    assert_eq!(parse_birthdate("01/01/2999"), None);
+
+    let input = "born,mother_born\n01/01/2999,\"\"\n03/14/85,02/30/85\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Patient::from_csv_reader(input.as_bytes()));
+    assert!(records.unwrap().is_empty());
+    let failed: Vec<_> = report.errors.iter().map(|error| error.row).collect();
+    assert_eq!(failed, vec![1, 2], "{:?}", report);
+}
+
+#[test]
+fn round_trips_through_csv() {
+    let admissions = Admission::from_csv_reader(ADMISSIONS.as_bytes()).unwrap();
+    assert_eq!(
+        to_csv(&admissions),
+        "admitted,scheduled\n03/14/69,03/14/29\n03/14/70,03/14/30\n03/14/69,03/14/29\n"
+    );
+
+    // Birthdates are written with four-digit years, so they read back the same
+    let input = "born,mother_born\n03/14/85,12/31/99\n";
+    let patients = Patient::from_csv_reader(input.as_bytes()).unwrap();
+    let written = to_csv(&patients);
+    assert_eq!(written, "born,mother_born\n03/14/1985,12/31/1999\n");
+    let reread = Patient::from_csv_reader(written.as_bytes()).unwrap();
+    assert_eq!(reread[0].born, patients[0].born);
+    assert_eq!(reread[0].mother_born, patients[0].mother_born);
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use common::to_csv;
+use deserialize::{
+    validate_string, validated_string, validated_string_opt, CharClass, FromCsv, LoadOptions,
+    StringRule,
+};
+use serde::{Deserialize, Serialize};
+
+struct ProcedureCode;
+
+impl StringRule for ProcedureCode {
+    const MIN_LENGTH: usize = 3;
+    const MAX_LENGTH: Option<usize> = Some(5);
+    const CHARACTERS: Option<CharClass> = Some(CharClass::AsciiAlphanumeric);
+}
+
+struct ZipCode;
+
+impl StringRule for ZipCode {
+    const PATTERN: Option<&'static str> = Some(r"\d{5}(-\d{4})?");
+}
+
+struct Sex;
+
+impl StringRule for Sex {
+    const ALLOWED: &'static [&'static str] = &["F", "M", "U"];
+    const IGNORE_CASE: bool = true;
+}
+
+struct BrokenPattern;
+
+impl StringRule for BrokenPattern {
+    const PATTERN: Option<&'static str> = Some("(");
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Claim {
+    #[serde(
+        deserialize_with = "validated_string::deserialize_as::<ProcedureCode, _>",
+        serialize_with = "validated_string::serialize"
+    )]
+    procedure: String,
+    #[serde(
+        deserialize_with = "validated_string_opt::deserialize_as::<ZipCode, _>",
+        serialize_with = "validated_string_opt::serialize"
+    )]
+    zip: Option<String>,
+    #[serde(
+        deserialize_with = "validated_string_opt::deserialize_as::<Sex, _>",
+        serialize_with = "validated_string_opt::serialize"
+    )]
+    sex: Option<String>,
+}
+
+impl FromCsv for Claim {}
+
+#[test]
+fn reads_values_that_follow_their_rules() {
+    let input = "procedure,zip,sex\n\
+                 \" 99213 \",02139,f\n\
+                 A12,02139-1234,NULL\n\
+                 J3490,\"\",\"\"\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Claim::from_csv_reader(input.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+
+    assert_eq!(records[0].procedure, "99213");
+    assert_eq!(records[0].zip.as_deref(), Some("02139"));
+    assert_eq!(records[0].sex.as_deref(), Some("f"));
+    assert_eq!(records[1].zip.as_deref(), Some("02139-1234"));
+    assert_eq!(records[1].sex, None);
+    assert_eq!(records[2].zip, None);
+
+    assert_eq!(
+        to_csv(&records),
+        "procedure,zip,sex\n99213,02139,f\nA12,02139-1234,\nJ3490,,\n"
+    );
+}
+
+#[test]
+fn names_the_value_that_breaks_a_rule() {
+    let input = "procedure,zip,sex\n99,,\n992130,,\n99-13,,\n";
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Claim::from_csv_reader(input.as_bytes()));
+    assert!(records.unwrap().is_empty());
+    let messages: Vec<_> = report
+        .errors
+        .iter()
+        .map(|error| error.message.as_str())
+        .collect();
+    assert_eq!(messages.len(), 3, "{:?}", report);
+    assert!(
+        messages[0].contains("\"99\" is shorter than 3"),
+        "{:?}",
+        messages
+    );
+    assert!(
+        messages[1].contains("\"992130\" is longer than 5"),
+        "{:?}",
+        messages
+    );
+    assert!(
+        messages[2].contains("\"99-13\" contains '-'"),
+        "{:?}",
+        messages
+    );
+
+    assert!(validate_string::<ZipCode>("2139")
+        .unwrap_err()
+        .contains("\"2139\""));
+    assert!(validate_string::<Sex>("X").unwrap_err().contains("\"X\""));
+    assert!(validate_string::<BrokenPattern>("abc")
+        .unwrap_err()
+        .contains("\"abc\""));
+}
+
+#[test]
+fn checks_typed_cells_as_their_text() {
+    let claim: Claim =
+        serde_json::from_str(r#"{"procedure": 99213, "zip": "02139", "sex": null}"#).unwrap();
+    assert_eq!(claim.procedure, "99213");
+    assert_eq!(claim.sex, None);
+
+    let err = serde_json::from_str::<Claim>(r#"{"procedure": 99, "zip": null, "sex": null}"#)
+        .unwrap_err();
+    assert!(err.to_string().contains("\"99\""), "{}", err);
+}
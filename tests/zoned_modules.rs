@@ -0,0 +1,154 @@
+#![cfg(feature = "chrono-tz")]
+
+mod common;
+
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use common::{csv_of, to_csv};
+use deserialize::{
+    offset_datetime, parse_zoned, utc_datetime_opt, AmbiguousTime, FromCsv, LoadOptions,
+    MmDdYyyyFormat, NonexistentTime, Zone,
+};
+use serde::{Deserialize, Serialize};
+
+struct Central;
+
+impl Zone for Central {
+    const TZ: chrono_tz::Tz = chrono_tz::America::Chicago;
+}
+
+struct CentralLatest;
+
+impl Zone for CentralLatest {
+    const TZ: chrono_tz::Tz = chrono_tz::America::Chicago;
+    const AMBIGUOUS: AmbiguousTime = AmbiguousTime::Latest;
+}
+
+struct CentralStrict;
+
+impl Zone for CentralStrict {
+    const TZ: chrono_tz::Tz = chrono_tz::America::Chicago;
+    const AMBIGUOUS: AmbiguousTime = AmbiguousTime::Reject;
+    const NONEXISTENT: NonexistentTime = NonexistentTime::Reject;
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct Visit {
+    #[serde(
+        deserialize_with = "offset_datetime::deserialize_in::<MmDdYyyyFormat, Central, _>",
+        serialize_with = "offset_datetime::serialize_as::<MmDdYyyyFormat, _>"
+    )]
+    arrived: DateTime<FixedOffset>,
+    #[serde(
+        deserialize_with = "utc_datetime_opt::deserialize_in::<MmDdYyyyFormat, CentralLatest, _>",
+        serialize_with = "utc_datetime_opt::serialize"
+    )]
+    left: Option<DateTime<Utc>>,
+}
+
+impl FromCsv for Visit {}
+
+#[derive(Debug, Deserialize)]
+struct StrictVisit {
+    #[serde(
+        deserialize_with = "offset_datetime::deserialize_in::<MmDdYyyyFormat, CentralStrict, _>"
+    )]
+    #[allow(dead_code)]
+    arrived: DateTime<FixedOffset>,
+}
+
+impl FromCsv for StrictVisit {}
+
+fn offset(hours: i32) -> FixedOffset {
+    FixedOffset::east_opt(hours * 3600).unwrap()
+}
+
+// Clocks in Chicago skipped 02:00-03:00 on 2021-03-14 and repeated 01:00-02:00 on 2021-11-07
+const INPUT: &str = "arrived,left\n\
+                     03/14/2021 02:30:00,11/07/2021 01:30:00\n\
+                     11/07/2021 01:30:00,\"\"\n\
+                     03/14/2021 07:30 -06:00,NULL\n";
+
+#[test]
+fn resolves_gaps_and_repeated_hours() {
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| Visit::from_csv_reader(INPUT.as_bytes()));
+    assert!(report.is_clean(), "{:?}", report);
+    let records = records.unwrap();
+
+    // ShiftForward keeps the offset from before the gap, so 02:30 CST is 03:30 CDT
+    assert_eq!(
+        records[0].arrived,
+        offset(-5).with_ymd_and_hms(2021, 3, 14, 3, 30, 0).unwrap()
+    );
+    // Latest is the second 01:30, on standard time
+    assert_eq!(
+        records[0].left,
+        Some(Utc.with_ymd_and_hms(2021, 11, 7, 7, 30, 0).unwrap())
+    );
+    // Earliest is the first 01:30, still on daylight time
+    assert_eq!(
+        records[1].arrived,
+        offset(-5).with_ymd_and_hms(2021, 11, 7, 1, 30, 0).unwrap()
+    );
+    assert_eq!(records[1].left, None);
+    // An explicit offset wins over the zone
+    assert_eq!(
+        records[2].arrived,
+        offset(-6).with_ymd_and_hms(2021, 3, 14, 7, 30, 0).unwrap()
+    );
+}
+
+#[test]
+fn rejects_times_the_zone_cannot_place() {
+    let input = csv_of(
+        "arrived",
+        &[
+            "03/14/2021 02:30:00",
+            "11/07/2021 01:30:00",
+            "11/08/2021 01:30:00",
+            "yesterday",
+        ],
+    );
+    let (records, report) =
+        LoadOptions::new().run_with_report(|| StrictVisit::from_csv_reader(input.as_bytes()));
+    assert_eq!(records.unwrap().len(), 1);
+    let failed: Vec<_> = report.errors.iter().map(|error| error.row).collect();
+    assert_eq!(failed, vec![1, 2, 4], "{:?}", report);
+    assert!(
+        report.errors[0].message.contains("nonexistent"),
+        "{:?}",
+        report
+    );
+    assert!(
+        report.errors[1].message.contains("ambiguous"),
+        "{:?}",
+        report
+    );
+
+    assert!(parse_zoned::<MmDdYyyyFormat, Central>("11/07/2021 01:30:00").is_ok());
+    assert!(parse_zoned::<MmDdYyyyFormat, CentralStrict>("11/07/2021 01:30:00").is_err());
+}
+
+#[test]
+fn round_trips_with_offsets() {
+    let records = Visit::from_csv_reader(INPUT.as_bytes()).unwrap();
+    let written = to_csv(&records);
+    assert_eq!(
+        written,
+        "arrived,left\n\
+         03/14/2021 03:30:00 -05:00,2021-11-07T07:30:00Z\n\
+         11/07/2021 01:30:00 -05:00,\n\
+         03/14/2021 07:30:00 -06:00,\n"
+    );
+
+    let reread: Vec<DateTime<FixedOffset>> = written
+        .lines()
+        .skip(1)
+        .map(|line| {
+            let arrived = line.split(',').next().unwrap();
+            parse_zoned::<MmDdYyyyFormat, Central>(arrived).unwrap()
+        })
+        .collect();
+    let arrived: Vec<_> = records.iter().map(|record| record.arrived).collect();
+    assert_eq!(reread, arrived);
+}